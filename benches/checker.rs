@@ -0,0 +1,154 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use deduct::check::Checker;
+use deduct::parse::{Proof, Sentence};
+
+type Lines = Vec<(u16, String, String)>;
+type Scenario<'a> = (&'static str, fn(u16) -> Lines, &'a Checker);
+
+fn parse(lines: &Lines) -> Proof {
+    let lines: Vec<_> = lines
+        .iter()
+        .map(|(d, s, c)| (*d, s.as_str(), c.as_str()) )
+        .collect();
+
+    Proof::parse(lines).expect("Benchmark proof should parse")
+}
+
+/// A flat proof that repeatedly introduces and eliminates a conjunction.
+fn linear(n: u16) -> Lines {
+    let mut lines = vec![
+        (0, "A".into(), "PR".into()),
+        (0, "B".into(), "PR".into()),
+    ];
+
+    for _ in 0..n {
+        let k = lines.len() as u16;
+
+        lines.push( (0, "A ∧ B".into(), "∧I 1, 2".into()) );
+        lines.push( (0, "A".into(), format!("∧E {}", k + 1)) );
+    }
+
+    lines
+}
+
+/// A proof that opens `n` nested subproofs, reiterates from the top level, and then
+/// discharges each subproof with →I.
+fn nested(n: u16) -> Lines {
+    let mut lines = vec![
+        (0, "A".into(), "PR".into())
+    ];
+
+    for d in 1..=n {
+        lines.push( (d, "B".into(), "PR".into()) );
+    }
+
+    lines.push( (n, "A".into(), "R 1".into()) );
+
+    let mut conclusion = String::from("A");
+
+    for d in (0..n).rev() {
+        let start = d + 2;
+        let end = lines.len() as u16;
+
+        conclusion = format!("B → ({conclusion})");
+        lines.push( (d, conclusion.clone(), format!("→I {start}-{end}")) );
+    }
+
+    lines
+}
+
+/// A proof with `n` consecutive (sibling) subproofs, each discharged with →I.
+fn wide(n: u16) -> Lines {
+    let mut lines = vec![
+        (0, "A".into(), "PR".into())
+    ];
+
+    for _ in 0..n {
+        let k = lines.len() as u16;
+
+        lines.push( (1, "B".into(), "PR".into()) );
+        lines.push( (1, "A".into(), "R 1".into()) );
+        lines.push( (0, "B → A".into(), format!("→I {}-{}", k + 1, k + 2)) );
+    }
+
+    lines
+}
+
+/// A proof with `n` consecutive strict subproofs, each discharged with □I.
+fn modal(n: u16) -> Lines {
+    let mut lines = vec![
+        (0, "□A".into(), "PR".into())
+    ];
+
+    for _ in 0..n {
+        let k = lines.len() as u16;
+
+        lines.push( (1, "□".into(), "PR".into()) );
+        lines.push( (1, "A".into(), "□E 1".into()) );
+        lines.push( (0, "□A".into(), format!("□I {}-{}", k + 1, k + 2)) );
+    }
+
+    lines
+}
+
+fn check_proof(c: &mut Criterion) {
+    let tfl = Checker::from_rulesets(&[true]);
+    let k = Checker::from_rulesets(&[true, false, true]);
+
+    let scenarios: [Scenario; 4] = [
+        ("linear", linear, &tfl),
+        ("nested", nested, &tfl),
+        ("wide", wide, &tfl),
+        ("modal", modal, &k),
+    ];
+
+    let mut group = c.benchmark_group("check_proof");
+
+    for (name, build, checker) in scenarios {
+        for n in [50, 200] {
+            let p = parse( &build(n) );
+
+            checker.check_proof(&p).expect("Benchmark proof should be valid");
+
+            group.bench_with_input(BenchmarkId::new(name, n), &p, |b, p| {
+                b.iter(|| checker.check_proof(p) )
+            });
+        }
+    }
+
+    group.finish();
+}
+
+fn parse_sentence(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_sentence");
+
+    for n in [10, 50] {
+        // ((((A ∧ B) ∨ C) → D) ...)
+        let mut nested = String::from("A");
+
+        for i in 0..n {
+            let atom = (b'B' + (i % 24) as u8) as char;
+            let op = ["∧", "∨", "→", "↔"][i % 4];
+
+            nested = format!("({nested} {op} {atom})");
+        }
+
+        Sentence::parse(&nested).expect("Benchmark sentence should parse");
+
+        group.bench_with_input(BenchmarkId::new("nested", n), &nested, |b, s| {
+            b.iter(|| Sentence::parse(s) )
+        });
+
+        let negated = format!("{}A", "¬".repeat(n));
+
+        group.bench_with_input(BenchmarkId::new("negated", n), &negated, |b, s| {
+            b.iter(|| Sentence::parse(s) )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, check_proof, parse_sentence);
+criterion_main!(benches);
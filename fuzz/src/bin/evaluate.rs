@@ -0,0 +1,67 @@
+#[macro_use]
+extern crate afl;
+extern crate deduct;
+
+use std::sync::OnceLock;
+
+use deduct::check::Checker;
+use deduct::check::rulesets::*;
+use deduct::parse::{Proof, Sentence};
+
+static CHECKER: OnceLock<Checker> = OnceLock::new();
+
+/// Like the main target, but the first line of the input holds
+/// (semicolon-separated) conclusions to evaluate the proof against.
+fn main() {
+    fuzz!(|data: &[u8]| {
+        let c = CHECKER.get_or_init(|| {
+            let mut c = Checker::new();
+            c.add_ruleset(TFL_BASIC);
+            c.add_ruleset(TFL_DERIVED);
+            c.add_ruleset(SYSTEM_K);
+            c.add_ruleset(SYSTEM_T);
+            c.add_ruleset(SYSTEM_S4);
+            c.add_ruleset(SYSTEM_S5);
+            c
+        });
+
+        let Ok(data) = std::str::from_utf8(&data) else {
+            return
+        };
+
+        let Some((conclusions, data)) = data.split_once('\n') else {
+            return
+        };
+
+        let conclusions: Vec<_> = conclusions
+            .split(';')
+            .filter_map(|c| Sentence::parse(c).ok() )
+            .collect();
+    
+        let chunks: Vec<_> = data.split(',').collect();
+    
+        if chunks.chunks_exact(3).remainder().len() != 0 {
+            return;
+        }
+    
+        let mut input = Vec::new();
+    
+        for chunk in chunks.chunks_exact(3) {
+            let d = chunk[0].trim();
+            let s = chunk[1].trim();
+            let c = chunk[2].trim();
+    
+            let Ok(d) = d.parse::<u16>() else {
+                return
+            };
+    
+            input.push(
+                (d, s, c)
+            )
+        }
+    
+        if let Ok(p) = Proof::parse(input) {
+            let _ = c.evaluate(&p, &conclusions);
+        }
+    });
+}
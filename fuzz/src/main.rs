@@ -4,7 +4,8 @@ extern crate deduct;
 
 use std::sync::OnceLock;
 
-use deduct::*;
+use deduct::check::Checker;
+use deduct::check::rulesets::*;
 
 static CHECKER: OnceLock<Checker> = OnceLock::new();
 
@@ -47,8 +48,6 @@ fn main() {
             )
         }
     
-        if let Ok(p) = Proof::parse(input) {
-            let _ = c.check_proof(&p);
-        }
+        let _ = c.check_input(input);
     });
 }
@@ -6,29 +6,217 @@ use std::collections::HashMap;
 use crate::parse::*;
 use crate::check::rules::*;
 
+pub use rules::{Rule, CheckError, Equality};
+
 pub type CheckErrors = Vec<(u16, CheckError)>;
 pub type Ruleset<'a> = &'a [(&'static str, &'static dyn Rule)];
 
+/// A parse or check error, attributed to the line it occurred on.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Diagnostic {
+    Parse(u16, ParseError),
+    Check(u16, CheckError),
+}
+
+impl Diagnostic {
+    /// The number of the line this diagnostic applies to.
+    pub fn line(&self) -> u16 {
+        match self {
+            Self::Parse(n, _) | Self::Check(n, _) => *n
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(n, e) => write!(f, "line {n}: {e}"),
+            Self::Check(n, e) => write!(f, "line {n}: {e}"),
+        }
+    }
+}
+
+/// The result of checking a single line, as reported by [`Checker::check_proof_verbose`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct LineReport {
+    pub line: u16,
+    /// The ID of the rule the line cites.
+    pub rule: String,
+    /// Why the rule doesn't justify the line, if it doesn't.
+    pub error: Option<CheckError>,
+}
+
+impl LineReport {
+    /// Whether the line is justified by its rule.
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// The overall result of checking a proof against its conclusion.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProofOutcome {
+    /// No errors, but the conclusion hasn't been reached yet (or there's no conclusion to reach.)
+    Valid,
+    /// No errors, but the proof ends inside a subproof at the given depth - so the
+    /// conclusion can't have been reached.
+    Unclosed(u16),
+    /// No errors, the conclusion has been reached, and no placeholders remain.
+    Complete,
+    /// The conclusion has been reached, but the proof still contains placeholder citations.
+    Incomplete,
+    /// The proof contains errors.
+    Invalid(CheckErrors),
+}
+
+impl ProofOutcome {
+    /// Work out the outcome of a proof from its [verbose reports](Checker::check_proof_verbose), given
+    /// whether its goal has been reached (or `None` if it has no goal) - without checking it again.
+    pub fn from_reports(p: &Proof, reports: Vec<LineReport>, reached: Option<bool>) -> Self {
+        let errors: CheckErrors = reports
+            .into_iter()
+            .filter_map(|r| Some( (r.line, r.error?) ) )
+            .collect();
+
+        if !errors.is_empty() {
+            return Self::Invalid(errors)
+        }
+
+        let Some(reached) = reached else {
+            return Self::Valid
+        };
+
+        let depth = p.lines.last().map(|l| l.d).unwrap_or(0);
+
+        match ( reached, p.contains_placeholders() ) {
+            (false, _) if depth > 0 => Self::Unclosed(depth),
+            (false, _)              => Self::Valid,
+            (true, false)           => Self::Complete,
+            (true, true)            => Self::Incomplete,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Checker {
-    rules: HashMap<&'static str, &'static dyn Rule>
+    rules: HashMap<&'static str, &'static dyn Rule>,
+    /// When set, rules treat sentences that only differ in the order of their main ∧, ∨ or ↔'s
+    /// operands as the same (see [`Sentence::equiv_commutative`]) - in cited lines as well as the line being checked.
+    /// 
+    /// Defaults to `false`.
+    pub lenient_commutativity: bool,
+    /// When set, ∧E may extract any conjunct nested inside conjunctions in a single step,
+    /// rather than only the left or right conjunct.
+    /// 
+    /// Defaults to `false`.
+    pub deep_conjunction_elim: bool,
+    /// When set, a line may only cite lines from its own subproof - anything from an enclosing
+    /// subproof (or the main proof) must be brought in with R first. R itself is exempt.
+    /// 
+    /// Defaults to `false`.
+    pub require_reiteration: bool,
 }
 
 impl Checker {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         let rules = HashMap::from(
-            [("PR", &Premise as &dyn Rule), ("?", &Premise as &dyn Rule)]
+            [
+                ("PR", &Premise as &dyn Rule),
+                ("AS", &Assumption as &dyn Rule),
+                ("?", &Premise as &dyn Rule)
+            ]
         );
 
-        Self { rules }
+        Self {
+            rules,
+            lenient_commutativity: false,
+            deep_conjunction_elim: false,
+            require_reiteration: false
+        }
     }
     
+    /// Create a checker from a set of ruleset flags, ordered as in [`ALL_RULESETS`](rulesets::ALL_RULESETS).
+    /// 
+    /// Basic TFL is always enabled, and the dependencies of each selected ruleset are enabled
+    /// alongside it (see [`RULESET_DEPENDENCIES`](rulesets::RULESET_DEPENDENCIES)) - so selecting
+    /// System S4 also enables System T, System K and Derived TFL.
+    pub fn from_rulesets(flags: &[bool]) -> Self {
+        use rulesets::*;
+
+        let mut enabled = [false; ALL_RULESETS.len()];
+
+        enable_ruleset(&mut enabled, 0);
+
+        for (i, _) in flags
+            .iter()
+            .take(enabled.len())
+            .enumerate()
+            .filter(|(_, f)| **f)
+        {
+            enable_ruleset(&mut enabled, i);
+        }
+
+        let mut checker = Self::new();
+
+        for (ruleset, _) in ALL_RULESETS
+            .iter()
+            .zip(enabled)
+            .filter(|(_, e)| *e)
+        {
+            checker.add_ruleset(ruleset);
+        }
+
+        checker
+    }
+
+    /// Create a checker from an arbitrary set of rule IDs, drawn from any of the rulesets.
+    /// 
+    /// Unknown IDs are ignored.
+    pub fn from_rule_ids<I, S>(ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>
+    {
+        let mut checker = Self::new();
+
+        for id in ids {
+            if let Some((id, rule)) = rulesets::all_rules().find(|(i, _)| *i == id.as_ref() ) {
+                checker.rules.insert(id, rule);
+            }
+        }
+
+        checker
+    }
+
+    /// Add every rule in a ruleset to the checker.
+    /// 
+    /// If a rule ID is already in use, the existing rule is silently replaced - so when
+    /// rulesets overlap, the one added last wins. Use [`try_add_ruleset`](Checker::try_add_ruleset)
+    /// to find out when this happens.
     pub fn add_ruleset(&mut self, ruleset: Ruleset) {
         for (id, rule) in ruleset {
             self.rules.insert(id, *rule);
         }
     }
 
+    /// Like [`add_ruleset`](Checker::add_ruleset), but reports the IDs of any existing rules
+    /// that were replaced.
+    /// 
+    /// The ruleset is added either way.
+    pub fn try_add_ruleset(&mut self, ruleset: Ruleset) -> Result<(), Vec<&'static str>> {
+        let overwritten: Vec<_> = ruleset
+            .iter()
+            .filter_map(|(id, rule)| self.rules.insert(id, *rule).map(|_| *id) )
+            .collect();
+
+        if !overwritten.is_empty() {
+            return Err(overwritten)
+        }
+
+        Ok(())
+    }
+
     pub fn del_ruleset(&mut self, ruleset: Ruleset) {
         for (id, _) in ruleset {
             self.rules.remove(id);
@@ -36,18 +224,11 @@ impl Checker {
     }
 
     pub fn check_proof(&self, p: &Proof) -> Result<(), CheckErrors> {
-        let mut errors = Vec::new();
-        
-        for line in &p.lines {
-            let Some(rule) = self.rules.get( line.c.r.as_str() ) else {
-                errors.push( (line.n, CheckError::NoSuchRule) );
-                continue;
-            };
-
-            if let Err(e) = rule.validate(p, line) {
-                errors.push( (line.n, e) )
-            }
-        }
+        let errors: CheckErrors = self
+            .check_proof_verbose(p)
+            .into_iter()
+            .filter_map(|r| Some( (r.line, r.error?) ) )
+            .collect();
 
         if !errors.is_empty() {
             return Err(errors)
@@ -55,6 +236,286 @@ impl Checker {
 
         Ok(())
     }
+
+    /// Check a proof, reporting on every line - including those that pass.
+    pub fn check_proof_verbose(&self, p: &Proof) -> Vec<LineReport> {
+        p.lines
+            .iter()
+            .map(|line| self.report_line(p, line) )
+            .collect()
+    }
+
+    /// Check line `n` of a proof on its own, without checking any other lines.
+    /// 
+    /// Returns `None` if the proof has no line `n`.
+    pub fn explain_line(&self, p: &Proof, n: u16) -> Option<LineReport> {
+        let line = p.lines.iter().find(|l| l.n == n)?;
+
+        Some( self.report_line(p, line) )
+    }
+
+    fn report_line(&self, p: &Proof, line: &Line) -> LineReport {
+        LineReport {
+            line: line.n,
+            rule: line.c.r.clone(),
+            error: self.check_line(p, line).err(),
+        }
+    }
+
+    /// Check the justification of a single line of a proof.
+    fn check_line(&self, p: &Proof, line: &Line) -> Result<(), CheckError> {
+        let Some(mut rule) = self.rules.get( line.c.r.as_str() ).copied() else {
+            // Tell apart rules that exist but are switched off from ones that don't exist at all.
+            return Err(match rulesets::ruleset_of(&line.c.r) {
+                Some(ruleset) => CheckError::RuleNotEnabled { rule: line.c.r.clone(), ruleset },
+                None => CheckError::NoSuchRule
+            })
+        };
+
+        if self.deep_conjunction_elim && line.c.r == "∧E" {
+            rule = &DeepConjunctionElim;
+        }
+
+        let eq = match self.lenient_commutativity {
+            true  => Equality::Commutative,
+            false => Equality::Exact
+        };
+
+        rule.validate(p, line, eq)?;
+
+        if self.require_reiteration && line.c.r != "R" {
+            // Validation passed, so the line exists and all its citations are in bounds.
+            let start = p
+                .line_range(line.n)
+                .and_then(|r| r.first() )
+                .map(|l| l.n)
+                .expect("Checked line should be in the proof");
+
+            if line.cited_lines().iter().any(|ln| matches!(ln, LineNumber::One(n) if *n < start) ) {
+                return Err(CheckError::NotReiterated)
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find a single rule application that justifies `goal` as a new line at the end of
+    /// the proof (outside of any subproof), returning the rule ID and the lines it cites.
+    /// 
+    /// Every enabled rule is tried against every combination of lines and subproofs the new line
+    /// could cite, so this is only meant for spotting the last step of a proof - it never looks further ahead.
+    /// Rules that cite nothing (like `PR`) are never suggested.
+    pub fn one_step_to(&self, p: &Proof, goal: &Sentence) -> Option<(&'static str, Vec<LineNumber>)> {
+        let n = p.len() as u16 + 1;
+
+        let mut q = copy_proof(p);
+
+        // A line outside of any subproof leaves every subproof as it was.
+        q.strict_zones.push(false);
+        q.lines.push(Line {
+            s: goal.clone(),
+            c: Citation { r: String::new(), l: vec![] },
+            n,
+            d: 0
+        });
+
+        // Sorted, so that the same proof always gets the same suggestion.
+        let mut rules: Vec<_> = self.rules
+            .iter()
+            .filter(|(_, r)| !r.line_ord().is_empty() )
+            .map(|(id, r)| (*id, *r))
+            .collect();
+
+        rules.sort_by_key(|(id, _)| *id);
+
+        rules.into_iter().find_map(|(id, rule)| {
+            let wildcards = vec![None; rule.line_ord().len()];
+
+            self.search_citations(&mut q, n, id, &wildcards)
+                .map(|cited| (id, cited))
+        })
+    }
+
+    /// Fill in the wildcards (`None`s) in the citations of line `n`, which uses the rule `id`, with
+    /// whichever lines or subproofs make the line check - returning the first full citation found, if any.
+    /// 
+    /// Like [`one_step_to`](Checker::one_step_to), this tries every combination of lines and
+    /// subproofs the line could cite, so should be used sparingly. Only the cited rule is tried, and
+    /// the line's own citation in `p` is ignored.
+    pub fn resolve_wildcards(&self, p: &Proof, n: u16, id: &str, cited: &[Option<LineNumber>]) -> Option<Vec<LineNumber>> {
+        p.line(n)?;
+
+        self.search_citations(&mut copy_proof(p), n, id, cited)
+    }
+
+    /// Try every combination of citations for line `n` of `q` under rule `id`, with `cited` fixing
+    /// the citations that aren't wildcards. The line's citation is left modified.
+    fn search_citations(&self, q: &mut Proof, n: u16, id: &str, cited: &[Option<LineNumber>]) -> Option<Vec<LineNumber>> {
+        let (sentence_access, subproof_access) = q.accessibility(n);
+
+        let sentences: Vec<_> = (1..n)
+            .filter(|i| sentence_access[*i as usize - 1] )
+            .map(LineNumber::One)
+            .collect();
+
+        let subproofs: Vec<_> = q
+            .subproofs()
+            .filter(|s| subproof_access[s.start as usize - 1] )
+            .map(|s| LineNumber::Many(s.start..=s.end))
+            .collect();
+
+        let types = self.rules.get(id)?.line_ord();
+
+        if types.len() != cited.len() {
+            return None
+        }
+
+        let options: Vec<&[LineNumber]> = types
+            .iter()
+            .zip(cited)
+            .map(|(t, c)| match (t, c) {
+                (_, Some(c))              => std::slice::from_ref(c),
+                (LineNumberType::One, _)  => sentences.as_slice(),
+                (LineNumberType::Many, _) => subproofs.as_slice(),
+            })
+            .collect();
+
+        if options.iter().any(|o| o.is_empty() ) {
+            return None
+        }
+
+        let i = n as usize - 1;
+
+        // Step through every combination of citations, odometer-style.
+        let mut picks = vec![0; options.len()];
+
+        loop {
+            let cited: Vec<_> = picks
+                .iter()
+                .zip(&options)
+                .map(|(i, o)| o[*i].clone())
+                .collect();
+
+            q.lines[i].c = Citation { r: id.to_owned(), l: cited };
+
+            if self.check_line(q, &q.lines[i]).is_ok() {
+                return Some( q.lines[i].c.l.clone() )
+            }
+
+            // Once every position has run through all its options, there's nothing left to try.
+            let i = (0..picks.len()).rev().find(|i| picks[*i] + 1 < options[*i].len() )?;
+
+            picks[i] += 1;
+            picks[i + 1..].fill(0);
+        }
+    }
+
+    /// Parse and check raw `(depth, sentence, citation)` input in one go.
+    /// 
+    /// Lines are only checked if the whole proof parses, so the diagnostics will either be all
+    /// parse errors or all check errors - in both cases, ordered by line.
+    /// 
+    /// ```
+    /// use deduct::check::{Checker, CheckError, Diagnostic};
+    /// use deduct::parse::ParseError;
+    /// 
+    /// let c = Checker::from_rulesets(&[true]);
+    /// 
+    /// assert!(c.check_input([
+    ///     (0, "A ^ B", "PR"),
+    ///     (0, "A", "^E 1"),
+    /// ]).is_ok());
+    /// 
+    /// assert_eq!(
+    ///     c.check_input([
+    ///         (0, "A ^ B", "PR"),
+    ///         (0, "C", "^E 1"),
+    ///     ]).unwrap_err(),
+    ///     vec![Diagnostic::Check(2, CheckError::BadUsage)]
+    /// );
+    /// 
+    /// assert_eq!(
+    ///     c.check_input([
+    ///         (0, "A ^", "PR"),
+    ///         (0, "A", ""),
+    ///     ]).unwrap_err(),
+    ///     vec![
+    ///         Diagnostic::Parse(1, ParseError::EmptySentence),
+    ///         Diagnostic::Parse(2, ParseError::EmptyCitation),
+    ///     ]
+    /// );
+    /// ```
+    pub fn check_input<'a, I>(&self, input: I) -> Result<(), Vec<Diagnostic>>
+    where
+        I: AsRef<[(u16, &'a str, &'a str)]>
+    {
+        let p = Proof::parse(input).map_err(|e| {
+            e.into_iter()
+                .map(|(n, e)| Diagnostic::Parse(n, e) )
+                .collect::<Vec<_>>()
+        })?;
+
+        self.check_proof(&p).map_err(|e| {
+            e.into_iter()
+                .map(|(n, e)| Diagnostic::Check(n, e) )
+                .collect()
+        })
+    }
+
+    /// Whether two proofs are of the same argument - that is, they have the same set of premises
+    /// and the same conclusion (their last lines outside of any subproof), regardless of the steps between.
+    /// 
+    /// Neither proof is checked; see [`both_valid`](Checker::both_valid).
+    pub fn same_argument(a: &Proof, b: &Proof) -> bool {
+        use std::collections::HashSet;
+
+        let a_premises: HashSet<_> = a.premises().collect();
+        let b_premises: HashSet<_> = b.premises().collect();
+
+        a_premises == b_premises && a.conclusion() == b.conclusion()
+    }
+
+    /// Whether both proofs check without errors.
+    pub fn both_valid(&self, a: &Proof, b: &Proof) -> bool {
+        self.check_proof(a).is_ok() && self.check_proof(b).is_ok()
+    }
+
+    /// Check a proof, and determine whether it has (fully) reached any of the given conclusions.
+    /// 
+    /// With no conclusions there's no goal to reach, so a proof without errors is always [`ProofOutcome::Valid`].
+    pub fn evaluate(&self, p: &Proof, conclusions: &[Sentence]) -> ProofOutcome {
+        let reached = (!conclusions.is_empty()).then(|| conclusions.iter().any(|c| p.reaches(c) ));
+
+        ProofOutcome::from_reports(p, self.check_proof_verbose(p), reached)
+    }
+
+    /// Check a proof against its own declared [goal](Proof::goal), if any.
+    pub fn evaluate_goal(&self, p: &Proof) -> ProofOutcome {
+        self.evaluate(p, p.goal.as_slice())
+    }
+
+    /// Check a proof against several conclusions that must *all* be reached, for multi-part exercises.
+    /// 
+    /// Also returns whether each conclusion has been reached, in the order given.
+    pub fn evaluate_each(&self, p: &Proof, conclusions: &[Sentence]) -> (ProofOutcome, Vec<bool>) {
+        let each: Vec<_> = conclusions.iter().map(|c| p.reaches(c) ).collect();
+        let reached = (!each.is_empty()).then(|| each.iter().all(|r| *r) );
+
+        (ProofOutcome::from_reports(p, self.check_proof_verbose(p), reached), each)
+    }
+}
+
+/// Copy a proof line by line, so that citations can be tried out on the copy.
+fn copy_proof(p: &Proof) -> Proof {
+    Proof {
+        strict_zones: p.strict_zones.clone(),
+        lines: p.lines
+            .iter()
+            .map(|l| Line { s: l.s.clone(), c: l.c.clone(), ..*l })
+            .collect(),
+        goal: p.goal.clone(),
+        subproof_ranges: p.subproof_ranges.clone(),
+    }
 }
 
 impl Default for Checker {
@@ -111,6 +572,344 @@ mod tests {
         };
     }
 
+    #[test]
+    fn checker_clone() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Checker>();
+
+        let mut a = Checker::new();
+        a.add_ruleset(TFL_BASIC);
+
+        let mut b = a.clone();
+        b.add_ruleset(TFL_DERIVED);
+
+        let p = Proof::parse([
+            (0, "A v B", "PR"),
+            (0, "~A", "PR"),
+            (0, "B", "DS 1 2"),
+        ]).unwrap();
+
+        assert!( a.check_proof(&p).is_err() );
+        assert!( b.check_proof(&p).is_ok() );
+    }
+
+    #[test]
+    fn checker_from_rulesets() {
+        let c = Checker::from_rulesets(&[false, false, false, false, false, true]);
+
+        // The modal systems bring Derived TFL along with them.
+        for ruleset in [TFL_BASIC, TFL_DERIVED, SYSTEM_K, SYSTEM_T, SYSTEM_S4, SYSTEM_S5] {
+            for (id, _) in ruleset {
+                assert!( c.rules.contains_key(id) )
+            }
+        }
+
+        for (id, _) in CLASSICAL_AXIOMS {
+            assert!( !c.rules.contains_key(id) )
+        }
+
+        let c = Checker::from_rulesets(&[true, false, false, false, false, false, true]);
+
+        for (id, _) in TFL_DERIVED {
+            assert!( !c.rules.contains_key(id) )
+        }
+    }
+
+    #[test]
+    fn ruleset_collisions() {
+        const A: Ruleset = &[("R", &Reiteration), ("X", &Explosion)];
+        const B: Ruleset = &[("DS", &DisjunctiveSyllogism), ("X", &Dne)];
+
+        let mut c = Checker::new();
+
+        assert_eq!(c.try_add_ruleset(A), Ok(()));
+        assert_eq!(c.try_add_ruleset(B), Err(vec!["X"]));
+        assert_eq!(c.rules.len(), 6);
+
+        let p = Proof::parse([
+            (0, "~~A", "PR"),
+            (0, "A", "X 1"),
+        ]).unwrap();
+
+        // The later ruleset wins.
+        assert!( c.check_proof(&p).is_ok() );
+    }
+
+    #[test]
+    fn checker_from_rule_ids() {
+        let c = Checker::from_rule_ids(["R", "∧I", "→I", "MT", "□E", "Nonexistent"]);
+
+        let mut ids: Vec<_> = c.rules.keys().copied().collect();
+        ids.sort();
+
+        assert_eq!(ids, ["?", "AS", "MT", "PR", "R", "→I", "∧I", "□E"]);
+
+        let p = Proof::parse([
+            (0, "A -> B", "PR"),
+            (0, "~B", "PR"),
+            (0, "~A", "MT 1 2"),
+            (0, "B", "->E 1 3"),
+        ]).unwrap();
+
+        assert_eq!(
+            c.check_proof(&p).unwrap_err(),
+            vec![(4, CheckError::RuleNotEnabled { rule: String::from("→E"), ruleset: "Basic TFL" })]
+        );
+    }
+
+    #[test]
+    fn ruleset_dependencies() {
+        let mut flags = [false; 7];
+
+        // The modal systems bring Derived TFL along with the systems they extend...
+        enable_ruleset(&mut flags, 4);
+        assert_eq!(flags, [true, true, true, true, true, false, false]);
+
+        enable_ruleset(&mut flags, 6);
+        assert_eq!(flags, [true, true, true, true, true, false, true]);
+
+        disable_ruleset(&mut flags, 3);
+        assert_eq!(flags, [true, true, true, false, false, false, true]);
+
+        // ...and can't be kept without it.
+        disable_ruleset(&mut flags, 1);
+        assert_eq!(flags, [true, false, false, false, false, false, true]);
+
+        enable_ruleset(&mut flags, 2);
+        disable_ruleset(&mut flags, 2);
+        assert_eq!(flags, [true, true, false, false, false, false, true]);
+
+        disable_ruleset(&mut flags, 0);
+        assert_eq!(flags, [false; 7]);
+    }
+
+    /// A `proptest` strategy for (likely invalid) proofs, citing random lines under the given rules.
+    fn arb_proof(rules: Vec<&'static str>) -> impl proptest::strategy::Strategy<Value = Vec<(u16, String, String)>> {
+        use proptest::prelude::*;
+
+        let line = (
+            0..3_u16,
+            arb_sentence(true),
+            proptest::sample::select(rules),
+            proptest::collection::vec( (1..12_u16, 0..3_u16), 0..3 )
+        );
+
+        proptest::collection::vec(line, 1..12).prop_map(|lines| {
+            lines
+                .into_iter()
+                .map(|(d, s, r, cited)| {
+                    let cited: Vec<_> = cited
+                        .into_iter()
+                        .map(|(n, len)| match len {
+                            0 => n.to_string(),
+                            _ => format!("{n}-{}", n + len)
+                        })
+                        .collect();
+
+                    (d, s.to_string(), format!("{r} {}", cited.join(", ")))
+                })
+                .collect()
+        })
+    }
+
+    /// A `proptest` strategy for TFL proofs built by applying rules to randomly chosen earlier lines.
+    /// 
+    /// Each rule usage has the right shape, but the cited lines may well be unavailable - so the
+    /// checker must still sort the valid inferences from the invalid ones.
+    fn arb_derivation() -> impl proptest::strategy::Strategy<Value = Vec<(u16, String, String)>> {
+        use proptest::prelude::*;
+
+        let step = (0..11_u8, any::<usize>(), any::<usize>(), arb_sentence(false));
+
+        proptest::collection::vec(step, 1..24).prop_map(|steps| {
+            let mut lines: Vec<(u16, Sentence, String)> = vec![];
+            let mut d = 0_u16;
+
+            for (kind, a, b, extra) in steps {
+                let n = lines.len();
+
+                // Pick a random earlier line, or one whose sentence satisfies a predicate.
+                let pick = |i: usize| (n > 0).then(|| (i % n + 1, &lines[i % n].1) );
+                let find = |i: usize, f: &dyn Fn(&Sentence) -> bool| {
+                    let found: Vec<_> = (0..n).filter(|j| f(&lines[*j].1) ).collect();
+                    (!found.is_empty()).then(|| found[i % found.len()] + 1)
+                };
+
+                let line = match (kind, pick(a)) {
+                    (0, _) if d < 3 => {
+                        d += 1;
+                        Some( (d, extra, String::from("PR")) )
+                    }
+                    (1 | 2, _) if d > 0 => {
+                        // Close the current subproof with →I or ¬I.
+                        let start = (0..n).rev().find(|j| lines[*j].0 == d && lines[*j].2 == "PR").unwrap();
+                        let (assumption, end) = (&lines[start].1, &lines[n - 1].1);
+
+                        d -= 1;
+
+                        match kind {
+                            1 => Some( (d, Sentence::Imp( assumption.clone().box_up(), end.clone().box_up() ), format!("→I {}-{n}", start + 1)) ),
+                            _ => Some( (d, assumption.negated(), format!("¬I {}-{n}", start + 1)) )
+                        }
+                    }
+                    (3, Some((i, s))) => Some( (d, s.clone(), format!("R {i}")) ),
+                    (4, Some((i, s))) => {
+                        let (j, t) = pick(b).unwrap();
+                        Some( (d, Sentence::Con( s.clone().box_up(), t.clone().box_up() ), format!("∧I {i}, {j}")) )
+                    }
+                    (5, Some((i, Sentence::Con(l, r)))) => {
+                        let s = if b % 2 == 0 { l } else { r };
+                        Some( (d, (**s).clone(), format!("∧E {i}")) )
+                    }
+                    (6, Some((i, Sentence::Imp(l, r)))) => {
+                        find(b, &|s| s == &**l).map(|j| (d, (**r).clone(), format!("→E {i}, {j}")) )
+                    }
+                    (7, Some((i, s))) => Some( (d, Sentence::Dis( s.clone().box_up(), extra.box_up() ), format!("∨I {i}")) ),
+                    (8, Some((i, s))) => {
+                        let neg = s.negated();
+                        find(b, &|s| s == &neg).map(|j| (d, Sentence::Signal('⊥'), format!("¬E {i}, {j}")) )
+                    }
+                    (9, Some((i, s))) if s.is_bot_signal() => Some( (d, extra, format!("X {i}")) ),
+                    (10, _) if d == 0 => Some( (0, extra, String::from("PR")) ),
+                    _ => None
+                };
+
+                lines.extend(line);
+            }
+
+            lines
+                .into_iter()
+                .map(|(d, s, c)| (d, s.to_string(), c) )
+                .collect()
+        })
+    }
+
+    /// Whether the premises truth-functionally entail the conclusion, by brute-force truth table.
+    fn entails(premises: &[&Sentence], conclusion: &Sentence) -> bool {
+        let mut atoms = conclusion.atoms();
+
+        for p in premises {
+            atoms.extend( p.atoms() );
+        }
+
+        let atoms: Vec<_> = atoms.into_iter().collect();
+
+        (0..1_u32 << atoms.len()).all(|row| {
+            let v = |c| {
+                let i = atoms.iter().position(|a| *a == c).unwrap();
+                row & (1 << i) != 0
+            };
+
+            !premises.iter().all(|p| p.evaluate(&v) == Some(true) ) || conclusion.evaluate(&v) == Some(true)
+        })
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn checker_never_panics(lines in arb_proof(rulesets::ALL_RULESETS.iter().flat_map(|r| r.iter().map(|(id, _)| *id) ).chain(["PR", "AS"]).collect())) {
+            let lines: Vec<_> = lines.iter().map(|(d, s, c)| (*d, s.as_str(), c.as_str()) ).collect();
+
+            if let Ok(p) = Proof::parse(lines) {
+                let _ = Checker::from_rulesets(&[true; 7]).check_proof(&p);
+            }
+        }
+
+        #[test]
+        fn checker_soundness(lines in arb_derivation()) {
+            let lines: Vec<_> = lines.iter().map(|(d, s, c)| (*d, s.as_str(), c.as_str()) ).collect();
+
+            let Ok(p) = Proof::parse(lines) else {
+                return Ok(())
+            };
+
+            // Every line before the first error is part of a valid proof,
+            // so each of its depth-zero lines must follow from the premises before it.
+            let first_error = Checker::from_rulesets(&[])
+                .check_proof(&p)
+                .err()
+                .and_then(|e| e.first().map(|(n, _)| *n) )
+                .unwrap_or(u16::MAX);
+
+            let mut premises = vec![];
+
+            for line in p.lines.iter().take_while(|l| l.n < first_error).filter(|l| l.d == 0) {
+                if line.is_premise() {
+                    premises.push(&line.s);
+                } else {
+                    proptest::prop_assert!(
+                        entails(&premises, &line.s),
+                        "line {} ({}) does not follow from its premises", line.n, line.s
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unavailable_causes() {
+        // Citing a line from a subproof that has already been discharged.
+        bad_proof! {
+            [TFL_BASIC],
+            [(5, CheckError::InDischargedSubproof { line: 2, start: 2, end: 3 })],
+            0, "A", "PR",
+            1, "B", "PR",
+            1, "B", "R 2",
+            0, "B -> B", "->I 2-3",
+            0, "B", "R 2",
+        }
+
+        // Citing an interior line of a discharged subproof names the subproof that can be cited instead.
+        bad_proof! {
+            [TFL_BASIC],
+            [(7, CheckError::InDischargedSubproof { line: 5, start: 4, end: 6 })],
+            0, "A", "PR",
+            0, "B", "PR",
+            0, "A ^ B", "^I 1, 2",
+            1, "C", "AS",
+            1, "C ^ A", "^I 4, 1",
+            1, "C", "^E 5",
+            0, "C ^ A", "R 5",
+        }
+
+        // The same goes for the interior of a subproof nested within a discharged one.
+        bad_proof! {
+            [TFL_BASIC],
+            [(7, CheckError::InDischargedSubproof { line: 3, start: 2, end: 5 })],
+            0, "A", "PR",
+            1, "B", "AS",
+            2, "C", "AS",
+            2, "C", "R 3",
+            1, "C -> C", "->I 3-4",
+            0, "B -> (C -> C)", "->I 2-5",
+            0, "C", "R 3",
+        }
+
+        assert_eq!(
+            CheckError::InDischargedSubproof { line: 5, start: 4, end: 6 }.to_string(),
+            "line 5 is inside subproof 4–6, which has been discharged; you can cite the subproof as 4-6 but not its individual lines"
+        );
+
+        // Citing a line from a (closed) sibling subproof.
+        bad_proof! {
+            [TFL_BASIC],
+            [(4, CheckError::InSiblingSubproof)],
+            0, "A", "PR",
+            1, "B", "PR",
+            1, "C", "PR",
+            1, "B", "R 2",
+        }
+
+        // Citing the subproof the current line is still inside of.
+        bad_proof! {
+            [TFL_BASIC],
+            [(4, CheckError::NotYetClosed)],
+            0, "A", "PR",
+            1, "B", "PR",
+            1, "A", "R 1",
+            1, "B -> A", "->I 2-3",
+        }
+    }
+
     #[test]
     fn reiteration() {
         proof! {
@@ -131,6 +930,434 @@ mod tests {
         };
     }
 
+    #[test]
+    fn citation_counts() {
+        let p = Proof::parse([
+            (0, "A v B", "PR 1"),
+            (1, "A", "PR"),
+            (1, "A", "R 2 1"),
+            (0, "A", "vE 1 2-3"),
+            (0, "A -> A", "->I"),
+        ]).unwrap();
+
+        let e = Checker::from_rulesets(&[]).check_proof(&p).unwrap_err();
+
+        let count = |rule: &str, expected, subproofs, found| CheckError::BadLineCount {
+            rule: rule.to_owned(),
+            expected,
+            subproofs,
+            found
+        };
+
+        assert_eq!(
+            e,
+            vec![
+                (1, count("PR", 0, 0, 1)),
+                (3, count("R", 1, 0, 2)),
+                (4, count("∨E", 3, 2, 2)),
+                (5, count("→I", 1, 1, 0)),
+            ]
+        );
+
+        let messages: Vec<_> = e.iter().map(|(_, e)| e.to_string() ).collect();
+
+        assert_eq!(
+            messages,
+            [
+                "PR expects no citations but you gave 1",
+                "R expects 1 line but you gave 2",
+                "∨E expects 3 citations (1 line and 2 subproofs) but you gave 2",
+                "→I expects 1 subproof but you gave 0",
+            ]
+        );
+    }
+
+    #[test]
+    fn citation_types() {
+        let p = Proof::parse([
+            (0, "A v B", "PR"),
+            (1, "A", "PR"),
+            (1, "A", "R 2"),
+            (1, "B", "PR"),
+            (1, "B", "R 4"),
+            (0, "A", "vE 2-3 2-3 4-5"),
+            (0, "A", "vE 1 2 4-5"),
+            (0, "A", "vE 1 2-3 4"),
+            (0, "B -> A", "->I 4"),
+        ]).unwrap();
+
+        let e = Checker::from_rulesets(&[]).check_proof(&p).unwrap_err();
+
+        let bad_type = |rule: &str, position, expected, found| CheckError::BadLineType {
+            rule: rule.to_owned(),
+            position,
+            expected,
+            found
+        };
+
+        assert_eq!(
+            e,
+            vec![
+                (6, bad_type("∨E", 1, LineNumberType::One, LineNumber::Many(2..=3))),
+                (7, bad_type("∨E", 2, LineNumberType::Many, LineNumber::One(2))),
+                (8, bad_type("∨E", 3, LineNumberType::Many, LineNumber::One(4))),
+                (9, bad_type("→I", 1, LineNumberType::Many, LineNumber::One(4))),
+            ]
+        );
+
+        assert_eq!(
+            e[0].1.to_string(),
+            "∨E's 1st citation must be a single line like 3, but you cited a subproof range 2-3"
+        );
+
+        assert_eq!(
+            e[1].1.to_string(),
+            "∨E's 2nd citation must be a subproof range like 3-5, but you cited a single line 2"
+        );
+
+        assert_eq!(
+            e[2].1.to_string(),
+            "∨E's 3rd citation must be a subproof range like 3-5, but you cited a single line 4"
+        );
+    }
+
+    #[test]
+    fn same_argument() {
+        let c = Checker::from_rulesets(&[true]);
+
+        let reference = Proof::parse([
+            (0, "A ^ B", "PR"),
+            (0, "B", "^E 1"),
+            (0, "A", "^E 1"),
+            (0, "B ^ A", "^I 2 3"),
+        ]).unwrap();
+
+        let student = Proof::parse([
+            (0, "A^B", "PR"),
+            (1, "C", "AS"),
+            (1, "A", "^E 1"),
+            (0, "C -> A", "->I 2-3"),
+            (0, "A", "->E 4 5"),
+            (0, "B", "^E 1"),
+            (0, "B ^ A", "^I 6 3"),
+        ]).unwrap();
+
+        assert!( Checker::same_argument(&reference, &student) );
+        assert!( !c.both_valid(&reference, &student) );
+
+        let student = Proof::parse([
+            (0, "A ^ B", "PR"),
+            (0, "A", "^E 1"),
+            (0, "B", "^E 1"),
+            (0, "B ^ A", "^I 3 2"),
+        ]).unwrap();
+
+        assert!( Checker::same_argument(&reference, &student) );
+        assert!( c.both_valid(&reference, &student) );
+
+        let different = Proof::parse([
+            (0, "A ^ B", "PR"),
+            (0, "A", "^E 1"),
+            (0, "A ^ A", "^I 2 2"),
+        ]).unwrap();
+
+        assert!( !Checker::same_argument(&reference, &different) );
+        assert!( c.both_valid(&reference, &different) );
+
+        let extra_premise = Proof::parse([
+            (0, "A ^ B", "PR"),
+            (0, "C", "PR"),
+            (0, "B", "^E 1"),
+            (0, "A", "^E 1"),
+            (0, "B ^ A", "^I 3 4"),
+        ]).unwrap();
+
+        assert!( !Checker::same_argument(&reference, &extra_premise) );
+    }
+
+    #[test]
+    fn require_reiteration() {
+        let direct = Proof::parse([
+            (0, "A", "PR"),
+            (1, "B", "AS"),
+            (1, "A ^ B", "^I 1 2"),
+            (0, "B -> (A ^ B)", "->I 2-3"),
+        ]).unwrap();
+
+        let reiterated = Proof::parse([
+            (0, "A", "PR"),
+            (1, "B", "AS"),
+            (1, "A", "R 1"),
+            (2, "C", "AS"),
+            (2, "B", "R 2"),
+            (2, "A", "R 3"),
+            (2, "A ^ B", "^I 6 5"),
+            (1, "C -> (A ^ B)", "->I 4-7"),
+            (0, "B -> (C -> (A ^ B))", "->I 2-8"),
+        ]).unwrap();
+
+        let mut c = Checker::from_rulesets(&[true]);
+
+        assert!( c.check_proof(&direct).is_ok() );
+        assert!( c.check_proof(&reiterated).is_ok() );
+
+        c.require_reiteration = true;
+
+        assert_eq!(
+            c.check_proof(&direct).unwrap_err(),
+            vec![(3, CheckError::NotReiterated)]
+        );
+
+        assert!( c.check_proof(&reiterated).is_ok() );
+    }
+
+    #[test]
+    fn rule_descriptions() {
+        for (id, _) in Checker::new().rules {
+            assert!( rulesets::describe_rule(id).is_some(), "{id} has no description" );
+        }
+
+        assert_eq!( rulesets::describe_rule("→E").unwrap(), "Conditional elimination: From A → B and A, conclude B." );
+        assert_eq!( rulesets::describe_rule("DQ"), None );
+    }
+
+    #[test]
+    fn catalog() {
+        let catalog = rulesets::catalog();
+
+        for (ruleset, name) in rulesets::ALL_RULESETS.iter().zip(rulesets::RULESET_NAMES) {
+            for (id, _) in *ruleset {
+                assert!(
+                    catalog.iter().any(|d| d.id == *id && d.ruleset == *name),
+                    "{id} from {name} is missing from the catalog"
+                );
+            }
+        }
+
+        for doc in &catalog {
+            assert!( !doc.schema.is_empty(), "{} has no entry in RULE_INFO", doc.id );
+        }
+
+        assert_eq!( catalog.len(), rulesets::all_rules().count() );
+
+        let ve = catalog.iter().find(|d| d.id == "∨E").unwrap();
+
+        assert_eq!(ve.ruleset, "Basic TFL");
+        assert_eq!(ve.name, "Disjunction elimination");
+        assert_eq!(ve.arity, "3 citations (1 line and 2 subproofs)");
+        assert!(!ve.strict_only);
+
+        assert!( catalog.iter().find(|d| d.id == "□E").unwrap().strict_only );
+    }
+
+    #[test]
+    fn rule_not_enabled() {
+        let p = Proof::parse([
+            (0, "A v B", "PR"),
+            (0, "~A", "PR"),
+            (0, "B", "DS 1 2"),
+            (0, "B", "DQ 1 2"),
+        ]).unwrap();
+
+        let e = Checker::from_rulesets(&[true]).check_proof(&p).unwrap_err();
+
+        assert_eq!(
+            e,
+            vec![
+                (3, CheckError::RuleNotEnabled { rule: String::from("DS"), ruleset: "Derived TFL" }),
+                (4, CheckError::NoSuchRule),
+            ]
+        );
+
+        assert_eq!(
+            e[0].1.to_string(),
+            "cited DS, which isn't enabled for this proof; it's part of Derived TFL"
+        );
+
+        assert!( Checker::from_rulesets(&[true, true]).check_proof(&p).is_err_and(|e| e == [(4, CheckError::NoSuchRule)]) );
+    }
+
+    #[test]
+    fn check_proof_verbose() {
+        let p = Proof::parse([
+            (0, "A ^ B", "PR"),
+            (0, "A", "^E 1"),
+            (0, "C", "^E 1"),
+            (0, "B", "XYZ 1"),
+        ]).unwrap();
+
+        let c = Checker::from_rulesets(&[true]);
+        let reports = c.check_proof_verbose(&p);
+
+        assert_eq!(
+            reports,
+            vec![
+                LineReport { line: 1, rule: String::from("PR"), error: None },
+                LineReport { line: 2, rule: String::from("∧E"), error: None },
+                LineReport { line: 3, rule: String::from("∧E"), error: Some(CheckError::BadUsage) },
+                LineReport { line: 4, rule: String::from("XYZ"), error: Some(CheckError::NoSuchRule) },
+            ]
+        );
+
+        assert_eq!(
+            reports.iter().map(LineReport::passed).collect::<Vec<_>>(),
+            vec![true, true, false, false]
+        );
+
+        assert_eq!(
+            c.check_proof(&p).unwrap_err(),
+            vec![(3, CheckError::BadUsage), (4, CheckError::NoSuchRule)]
+        );
+
+        assert_eq!(c.explain_line(&p, 3).as_ref(), reports.get(2));
+        assert_eq!(c.explain_line(&p, 5), None);
+    }
+
+    #[test]
+    fn one_step_to() {
+        let c = Checker::from_rulesets(&[true]);
+
+        let p = Proof::parse([
+            (0, "A", "PR"),
+            (0, "A -> B", "PR"),
+            (0, "C", "PR"),
+        ]).unwrap();
+
+        assert_eq!(
+            c.one_step_to(&p, &Sentence::parse("B").unwrap()),
+            Some( ("→E", vec![LineNumber::One(1), LineNumber::One(2)]) )
+        );
+
+        assert_eq!(
+            c.one_step_to(&p, &Sentence::parse("A ^ C").unwrap()),
+            Some( ("∧I", vec![LineNumber::One(1), LineNumber::One(3)]) )
+        );
+
+        assert_eq!(c.one_step_to(&p, &Sentence::parse("D").unwrap()), None);
+        assert_eq!(c.one_step_to(&p, &Sentence::parse("B ^ C").unwrap()), None);
+
+        // Subproofs can be cited, but lines inside them can't.
+        let p = Proof::parse([
+            (0, "B", "PR"),
+            (1, "A", "AS"),
+            (1, "B", "R 1"),
+        ]).unwrap();
+
+        assert_eq!(
+            c.one_step_to(&p, &Sentence::parse("A -> B").unwrap()),
+            Some( ("→I", vec![LineNumber::Many(2..=3)]) )
+        );
+
+        assert_eq!(c.one_step_to(&p, &Sentence::parse("A ^ B").unwrap()), None);
+    }
+
+    #[test]
+    fn resolve_wildcards() {
+        let c = Checker::from_rulesets(&[true]);
+
+        let p = Proof::parse([
+            (0, "A", "PR"),
+            (0, "B -> C", "PR"),
+            (0, "A -> B", "PR"),
+            (1, "D", "AS"),
+            (1, "A", "R 1"),
+            (0, "B", "?"),
+            (0, "D -> A", "?"),
+        ]).unwrap();
+
+        assert_eq!(
+            c.resolve_wildcards(&p, 6, "→E", &[None, None]),
+            Some( vec![LineNumber::One(1), LineNumber::One(3)] )
+        );
+
+        assert_eq!(
+            c.resolve_wildcards(&p, 6, "→E", &[Some(LineNumber::One(3)), None]),
+            Some( vec![LineNumber::One(3), LineNumber::One(1)] )
+        );
+
+        assert_eq!(
+            c.resolve_wildcards(&p, 7, "→I", &[None]),
+            Some( vec![LineNumber::Many(4..=5)] )
+        );
+
+        // Lines inside the closed subproof are never tried.
+        assert_eq!(c.resolve_wildcards(&p, 7, "R", &[None]), None);
+        assert_eq!(c.resolve_wildcards(&p, 6, "→E", &[Some(LineNumber::One(2)), None]), None);
+        assert_eq!(c.resolve_wildcards(&p, 6, "→E", &[None]), None);
+        assert_eq!(c.resolve_wildcards(&p, 6, "Nonexistent", &[None]), None);
+        assert_eq!(c.resolve_wildcards(&p, 8, "R", &[None]), None);
+    }
+
+    #[test]
+    fn malformed_citations() {
+        // Rules are never run on malformed citations by the checker,
+        // but shouldn't panic if they are.
+        let p = Proof::parse([
+            (0, "A ^ B", "PR"),
+            (0, "A", "^E 99"),
+            (0, "A", "^E 1-2"),
+            (0, "A", "^E"),
+            (1, "B", "AS"),
+            (1, "A", "R 3"),
+            (0, "B -> A", "->I 5"),
+            (0, "B -> A", "->I 5-99"),
+        ]).unwrap();
+
+        let run = |rule: &dyn Rule, n| rule.is_right(&p, p.line(n).unwrap(), Equality::Exact);
+
+        assert_eq!(run(&ConjunctionElim, 2), Err(CheckError::BadLine));
+        assert_eq!(run(&ConjunctionElim, 4), Err(CheckError::BadLine));
+        assert_eq!(
+            run(&ConjunctionElim, 3),
+            Err(CheckError::BadLineType {
+                rule: String::from("∧E"),
+                position: 1,
+                expected: LineNumberType::One,
+                found: LineNumber::Many(1..=2)
+            })
+        );
+
+        assert_eq!(run(&ConditionalIntr, 8), Err(CheckError::BadLine));
+        assert_eq!(
+            run(&ConditionalIntr, 7),
+            Err(CheckError::BadLineType {
+                rule: String::from("→I"),
+                position: 1,
+                expected: LineNumberType::Many,
+                found: LineNumber::One(5)
+            })
+        );
+    }
+
+    #[test]
+    fn repeated_citations() {
+        proof! {
+            [TFL_BASIC],
+            0, "A", "PR",
+            0, "A v A", "PR",
+            0, "A ^ A", "^I 1 1",
+            1, "A", "PR",
+            1, "A ^ A", "^I 4 4",
+            1, "A", "R 4",
+            0, "A <-> A", "<->I 4-6 4-6",
+            0, "A", "vE 2 4-6 4-6",
+        }
+
+        // Citing a conditional or biconditional twice can never be right - but
+        // it's the rule that should say so, not the line or accessibility checks.
+        bad_proof! {
+            [TFL_BASIC],
+            [
+                (3, CheckError::BadUsage),
+                (4, CheckError::BadUsage),
+            ],
+            0, "A -> A", "PR",
+            0, "A <-> A", "PR",
+            0, "A", "->E 1 1",
+            0, "A", "<->E 2 2",
+        }
+    }
+
     #[test]
     fn conjunction_elim() {
         proof! {
@@ -166,6 +1393,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn disjunction_elim_bot() {
+        // Both cases can reach ⊥, and so can the conclusion.
+        proof! {
+            [TFL_BASIC],
+            0, "A v B", "PR",
+            0, "~A", "PR",
+            0, "~B", "PR",
+            1, "A", "PR",
+            1, "#", "~E 4, 2",
+            1, "B", "PR",
+            1, "#", "~E 6, 3",
+            0, "#", "vE 1, 4-5, 6-7",
+        }
+
+        // ...but a ⊥ conclusion still needs both cases to reach it.
+        bad_proof! {
+            [TFL_BASIC],
+            [(8, CheckError::BadUsage)],
+            0, "A v B", "PR",
+            0, "~A", "PR",
+            0, "~B", "PR",
+            1, "A", "PR",
+            1, "#", "~E 4, 2",
+            1, "B", "PR",
+            1, "B", "R 6",
+            0, "#", "vE 1, 4-5, 6-7",
+        }
+    }
+
     #[test]
     fn conditional_intr() {
         proof! {
@@ -256,6 +1513,76 @@ mod tests {
         }
     }
 
+    #[test]
+    fn bottom_intr() {
+        // Every spelling of ⊥ works, both in the sentence and the rule.
+        proof! {
+            [TFL_BASIC],
+            0, "A", "PR",
+            0, "~A", "PR",
+            0, "#", "XI 1 2",
+            0, "XX", "XI 2 1",
+            0, "#", "#I 1, 2",
+            0, "⊥", "XXI 2, 1",
+            0, "B", "X 6",
+        }
+
+        proof! {
+            [TFL_BASIC],
+            0, "~A", "PR",
+            1, "A", "AS",
+            1, "#", "⊥I 2, 1",
+            0, "~A", "~I 2-3",
+        }
+
+        bad_proof! {
+            [TFL_BASIC],
+            [
+                (3, CheckError::BadUsage),
+                (4, CheckError::BadUsage),
+            ],
+            0, "A", "PR",
+            0, "~A", "PR",
+            0, "A", "XI 1 2",
+            0, "#", "XI 1 1",
+        }
+    }
+
+    #[test]
+    fn pure_natural_deduction() {
+        let pure = Proof::parse([
+            (0, "A v B", "PR"),
+            (0, "~A", "PR"),
+            (1, "A", "AS"),
+            (1, "#", "~E 3, 2"),
+            (1, "B", "X 4"),
+            (1, "B", "AS"),
+            (1, "B", "R 6"),
+            (0, "B", "vE 1, 3-5, 6-7"),
+        ]).unwrap();
+
+        assert!( pure.is_pure_natural_deduction() );
+        assert!( pure.uses_only(&["∨E", "¬E", "X", "R"]) );
+        assert!( !pure.uses_only(&["∨E", "¬E", "R"]) );
+
+        let derived = Proof::parse([
+            (0, "A v B", "PR"),
+            (0, "~A", "PR"),
+            (0, "B", "DS 1, 2"),
+        ]).unwrap();
+
+        assert!( !derived.is_pure_natural_deduction() );
+        assert!( derived.uses_only(&["DS"]) );
+
+        // Placeholders still need a real justification.
+        let unfinished = Proof::parse([
+            (0, "A", "PR"),
+            (0, "A ^ A", "?"),
+        ]).unwrap();
+
+        assert!( !unfinished.is_pure_natural_deduction() );
+    }
+
     #[test]
     fn indirect_proof() {
         proof! {
@@ -351,18 +1678,437 @@ mod tests {
             0, "(A v B) v C", "PR",
             1, "A v B", "PR",
             2, "A", "PR",
-            2, "A v (B v C)", "vI 3",
-            2, "B", "PR",
-            2, "B v C", "vI 5",
-            2, "A v (B v C)", "vI 6",
-            1, "A v (B v C)", "vE 2 3-4 5-7",
+            2, "A v (B v C)", "vI 3",
+            2, "B", "PR",
+            2, "B v C", "vI 5",
+            2, "A v (B v C)", "vI 6",
+            1, "A v (B v C)", "vE 2 3-4 5-7",
+            1, "C", "PR",
+            1, "B v C", "vI 9",
+            1, "A v (B v C)", "vI 10",
+            0, "A v (B v C)", "vE 1 2-8 9-11",
+        }
+    }
+
+    #[test]
+    fn theorem() {
+        proof! {
+            [TFL_BASIC],
+            1, "A", "PR",
+            1, "A", "R 1",
+            0, "A -> A", "->I 1-2",
+        }
+
+        let p = Proof::parse([
+            (1, "A", "PR"),
+            (1, "A", "R 1"),
+            (0, "A -> A", "->I 1-2"),
+        ]).unwrap();
+
+        assert!( p.reached_conclusion("A -> A") );
+        assert!( !p.reached_conclusion("A") );
+    }
+
+    #[test]
+    fn modal_conclusion() {
+        let p = Proof::parse([
+            (0, "[]P", "PR"),
+            (1, "[]", "AS"),
+            (1, "P", "[]E 1"),
+            (1, "P v R", "vI 3"),
+            (0, "[](P v R)", "[]I 2-4"),
+        ]).unwrap();
+
+        let conclusion = Sentence::parse( &normalize_ops("[](P v R)") ).unwrap();
+
+        assert_eq!(conclusion.to_string(), "□(P ∨ R)");
+        assert!( p.reached_conclusion("[](P v R)") );
+        assert!( p.reached_conclusion("□(P ∨ R)") );
+        assert!( !p.reached_conclusion("<>(P v R)") );
+
+        let c = Checker::from_rulesets(&[false, false, true]);
+
+        assert_eq!(c.evaluate(&p, &[conclusion]), ProofOutcome::Complete);
+    }
+
+    #[test]
+    fn assumption() {
+        proof! {
+            [TFL_BASIC],
+            0, "A", "PR",
+            1, "B", "AS",
+            1, "A", "R 1",
+            0, "B -> A", "->I 2-3",
+            1, "C", "PR",
+            1, "A", "R 1",
+            0, "C -> A", "->I 5-6",
+            1, "[]", "AS",
+        }
+
+        bad_proof! {
+            [TFL_BASIC],
+            [(2, CheckError::TopLevelAssumption)],
+            0, "A", "PR",
+            0, "B", "AS",
+        }
+    }
+
+    #[test]
+    fn overlapping_citations() {
+        bad_proof! {
+            [TFL_BASIC],
+            [(6, CheckError::OverlappingCitations { first: LineNumber::One(2), second: LineNumber::Many(2..=3) })],
+            0, "A v A", "PR",
+            1, "A", "PR",
+            1, "A", "R 2",
+            1, "A", "PR",
+            1, "A", "R 4",
+            0, "A", "vE 2 2-3 4-5",
+        }
+
+        bad_proof! {
+            [TFL_BASIC],
+            [(5, CheckError::OverlappingCitations { first: LineNumber::Many(2..=4), second: LineNumber::Many(3..=4) })],
+            0, "A v A", "PR",
+            1, "A", "PR",
+            2, "A", "PR",
+            2, "A", "R 3",
+            0, "A", "vE 1 2-4 3-4",
+        }
+    }
+
+    #[test]
+    fn late_premise() {
+        bad_proof! {
+            [TFL_BASIC],
+            [(3, CheckError::LatePremise)],
+            0, "A", "PR",
+            0, "A v C", "vI 1",
+            0, "B", "PR",
+            0, "A ^ B", "^I 1 3",
+        }
+
+        bad_proof! {
+            [TFL_BASIC],
+            [(4, CheckError::LatePremise)],
+            0, "A", "PR",
+            1, "B", "AS",
+            1, "A", "R 1",
+            0, "C", "PR",
+        }
+
+        // Placeholders aren't premises, and may appear anywhere.
+        proof! {
+            [TFL_BASIC],
+            0, "A", "PR",
+            0, "B", "PR",
+            0, "A ^ B", "^I 1 2",
+            0, "C", "?",
+        }
+
+        let p = Proof::parse([
+            (0, "A", "PR"),
+            (0, "A v C", "vI 1"),
+            (0, "C", "PR"),
+        ]).unwrap();
+
+        // A late premise still counts towards the argument, but the proof never checks.
+        assert_eq!(p.premises().count(), 2);
+        assert_eq!(
+            Checker::from_rulesets(&[true]).evaluate(&p, &[p.conclusion().unwrap().clone()]),
+            ProofOutcome::Invalid( vec![(3, CheckError::LatePremise)] )
+        );
+    }
+
+    #[test]
+    fn evaluate() {
+        let c = Checker::from_rulesets(&[]);
+        let conclusion = [Sentence::parse("A ^ B").unwrap()];
+
+        let outcome = |lines: &[(u16, &str, &str)]| {
+            c.evaluate( &Proof::parse(lines).unwrap(), &conclusion )
+        };
+
+        assert_eq!(
+            outcome(&[
+                (0, "A", "PR"),
+                (0, "B", "PR"),
+                (0, "A ^ B", "^I 1, 2"),
+            ]),
+            ProofOutcome::Complete
+        );
+
+        assert_eq!(
+            outcome(&[
+                (0, "A", "PR"),
+                (0, "B", "PR"),
+                (0, "A", "R 1"),
+            ]),
+            ProofOutcome::Valid
+        );
+
+        assert_eq!(
+            outcome(&[
+                (0, "A", "PR"),
+                (0, "B", "?"),
+                (0, "A ^ B", "^I 1, 2"),
+            ]),
+            ProofOutcome::Incomplete
+        );
+
+        assert_eq!(
+            outcome(&[
+                (0, "A", "PR"),
+                (0, "A ^ B", "^I 1, 2"),
+            ]),
+            ProofOutcome::Invalid( vec![(2, CheckError::BadLine)] )
+        );
+
+        assert_eq!(
+            outcome(&[
+                (0, "A", "PR"),
+                (1, "B", "PR"),
+                (2, "A", "PR"),
+                (2, "B", "R 2"),
+            ]),
+            ProofOutcome::Unclosed(2)
+        );
+
+        // Reaching any one of several alternative conclusions is enough.
+        let alternatives = [
+            Sentence::parse("A ^ B").unwrap(),
+            Sentence::parse("B ^ A").unwrap(),
+        ];
+
+        let p = Proof::parse([
+            (0, "A", "PR"),
+            (0, "B", "PR"),
+            (0, "B ^ A", "^I 2, 1"),
+        ]).unwrap();
+
+        assert_eq!(c.evaluate(&p, &alternatives), ProofOutcome::Complete);
+        assert_eq!(c.evaluate(&p, &alternatives[..1]), ProofOutcome::Valid);
+
+        // Without a fixed goal, a proof is only ever checked for errors.
+        let p = Proof::parse([
+            (0, "A", "PR"),
+            (0, "B", "?"),
+            (1, "A ^ B", "^I 1, 2"),
+        ]).unwrap();
+
+        assert_eq!(c.evaluate(&p, &[]), ProofOutcome::Valid);
+        assert_eq!(c.evaluate(&p, &conclusion), ProofOutcome::Unclosed(1));
+    }
+
+    #[test]
+    fn evaluate_goal() {
+        let c = Checker::from_rulesets(&[true]);
+        let lines = [
+            (0, "A", "PR"),
+            (0, "B", "PR"),
+            (0, "A ^ B", "^I 1, 2"),
+        ];
+
+        // Parsed proofs have no goal, so are only checked for errors...
+        let p = Proof::parse(lines).unwrap();
+
+        assert_eq!(p.goal, None);
+        assert_eq!(c.evaluate_goal(&p), ProofOutcome::Valid);
+
+        // ...unless one is declared.
+        let p = p.with_goal( Sentence::parse("A ^ B").unwrap() );
+        assert_eq!(c.evaluate_goal(&p), ProofOutcome::Complete);
+
+        let p = Proof::parse(lines).unwrap().with_goal( Sentence::parse("B ^ A").unwrap() );
+        assert_eq!(c.evaluate_goal(&p), ProofOutcome::Valid);
+    }
+
+    #[test]
+    fn evaluate_each() {
+        let c = Checker::from_rulesets(&[true]);
+        let required = [
+            Sentence::parse("B").unwrap(),
+            Sentence::parse("C").unwrap(),
+        ];
+
+        // Reaching one of two required conclusions isn't enough...
+        let p = Proof::parse([
+            (0, "B ^ C", "PR"),
+            (0, "B", "^E 1"),
+        ]).unwrap();
+
+        assert_eq!(c.evaluate_each(&p, &required), (ProofOutcome::Valid, vec![true, false]));
+        assert_eq!(c.evaluate(&p, &required), ProofOutcome::Complete);
+
+        // ...but reaching both is, in any order.
+        let p = Proof::parse([
+            (0, "B ^ C", "PR"),
+            (0, "C", "^E 1"),
+            (0, "B", "^E 1"),
+        ]).unwrap();
+
+        assert_eq!(c.evaluate_each(&p, &required), (ProofOutcome::Complete, vec![true, true]));
+
+        let p = Proof::parse([
+            (0, "B ^ C", "PR"),
+            (0, "C", "?"),
+            (0, "B", "^E 1"),
+        ]).unwrap();
+
+        assert_eq!(c.evaluate_each(&p, &required), (ProofOutcome::Incomplete, vec![true, true]));
+        assert_eq!(c.evaluate_each(&p, &[]), (ProofOutcome::Valid, vec![]));
+    }
+
+    #[test]
+    fn subordinate_subproofs() {
+        bad_proof! {
+            [TFL_BASIC],
+            [(5, CheckError::NotSubordinate)],
+            1, "A", "PR",
+            2, "B", "PR",
+            2, "B", "R 2",
+            1, "B -> B", "->I 2-3",
+            0, "B -> B", "->I 2-3",
+        }
+
+        bad_proof! {
+            [TFL_BASIC],
+            [(5, CheckError::NotSubordinate)],
+            1, "~B", "PR",
+            2, "B", "PR",
+            2, "#", "~E 1, 2",
+            1, "~B", "R 1",
+            0, "~B", "~I 2-3",
+        }
+
+        bad_proof! {
+            [TFL_BASIC],
+            [(5, CheckError::NotSubordinate)],
+            1, "B", "PR",
+            2, "~B", "PR",
+            2, "#", "~E 1, 2",
+            1, "B", "R 1",
+            0, "B", "IP 2-3",
+        }
+
+        bad_proof! {
+            [TFL_BASIC],
+            [(8, CheckError::NotSubordinate)],
+            0, "A v B", "PR",
+            1, "C", "PR",
+            2, "A", "PR",
+            2, "C", "R 2",
+            2, "B", "PR",
+            2, "C", "R 2",
+            1, "C", "R 2",
+            0, "C", "vE 1, 3-4, 5-6",
+        }
+
+        bad_proof! {
+            [TFL_BASIC],
+            [(7, CheckError::NotSubordinate)],
+            1, "C", "PR",
+            2, "A", "PR",
+            2, "A", "R 2",
+            2, "A", "PR",
+            2, "A", "R 4",
+            1, "C", "R 1",
+            0, "A <-> A", "<->I 2-3, 4-5",
+        }
+
+        bad_proof! {
+            [TFL_BASIC, TFL_DERIVED],
+            [(8, CheckError::NotSubordinate)],
+            0, "B", "PR",
             1, "C", "PR",
-            1, "B v C", "vI 9",
-            1, "A v (B v C)", "vI 10",
-            0, "A v (B v C)", "vE 1 2-8 9-11",
+            2, "A", "PR",
+            2, "B", "R 1",
+            2, "~A", "PR",
+            2, "B", "R 1",
+            1, "C", "R 2",
+            0, "B", "LEM 3-4 5-6",
         }
     }
 
+    #[test]
+    fn lenient_commutativity() {
+        let p = Proof::parse([
+            (0, "A -> (B ^ C)", "PR"),
+            (0, "A", "PR"),
+            (0, "C ^ B", "->E 1, 2"),
+            (0, "B ^ C", "R 3"),
+            (0, "B -> C", "R 1"),
+        ]).unwrap();
+
+        let mut c = Checker::from_rulesets(&[]);
+
+        assert_eq!(
+            c.check_proof(&p).unwrap_err(),
+            vec![
+                (3, CheckError::BadUsage),
+                (4, CheckError::BadUsage),
+                (5, CheckError::BadUsage),
+            ]
+        );
+
+        c.lenient_commutativity = true;
+
+        // Non-commutative connectives are still checked strictly.
+        assert_eq!(
+            c.check_proof(&p).unwrap_err(),
+            vec![(5, CheckError::BadUsage)]
+        );
+
+        // Cited sentences get the same leeway as the line's own.
+        let p = Proof::parse([
+            (0, "(A ^ B) -> C", "PR"),
+            (0, "B ^ A", "PR"),
+            (0, "(A ^ B) ^ D", "PR"),
+            (0, "C", "->E 1, 2"),
+            (0, "B ^ A", "^E 3"),
+        ]).unwrap();
+
+        assert_eq!( c.check_proof(&p), Ok(()) );
+
+        c.lenient_commutativity = false;
+
+        assert_eq!(
+            c.check_proof(&p).unwrap_err(),
+            vec![
+                (4, CheckError::BadUsage),
+                (5, CheckError::BadUsage),
+            ]
+        );
+    }
+
+    #[test]
+    fn deep_conjunction_elim() {
+        let p = Proof::parse([
+            (0, "A ^ (B ^ C)", "PR"),
+            (0, "B", "^E 1"),
+            (0, "B ^ C", "^E 1"),
+            (0, "A", "^E 1"),
+            (0, "D", "^E 1"),
+        ]).unwrap();
+
+        let mut c = Checker::from_rulesets(&[]);
+
+        assert_eq!(
+            c.check_proof(&p).unwrap_err(),
+            vec![
+                (2, CheckError::BadUsage),
+                (5, CheckError::BadUsage),
+            ]
+        );
+
+        c.deep_conjunction_elim = true;
+
+        assert_eq!(
+            c.check_proof(&p).unwrap_err(),
+            vec![(5, CheckError::BadUsage)]
+        );
+    }
+
     #[test]
     fn disjunctive_syllogism() {
         proof! {
@@ -419,6 +2165,34 @@ mod tests {
             0, "B", "LEM 2-3 4-5",
             0, "B", "LEM 4-5 2-3",
         }
+
+        // The negated assumption can come first, too.
+        proof! {
+            [TFL_BASIC, TFL_DERIVED],
+            0, "B", "PR",
+            1, "~A", "PR",
+            1, "B", "R 1",
+            1, "A", "PR",
+            1, "B", "R 1",
+            0, "B", "LEM 2-3 4-5",
+            0, "B", "LEM 4-5 2-3",
+        }
+
+        bad_proof! {
+            [TFL_BASIC, TFL_DERIVED],
+            [
+                (7, CheckError::MismatchedConclusions { first: String::from("B"), second: String::from("C") }),
+                (8, CheckError::BadUsage),
+            ],
+            0, "B", "PR",
+            0, "C", "PR",
+            1, "~A", "PR",
+            1, "B", "R 1",
+            1, "A", "PR",
+            1, "C", "R 2",
+            0, "B", "LEM 3-4 5-6",
+            0, "C", "LEM 3-4 3-4",
+        }
     }
 
     #[test]
@@ -448,11 +2222,122 @@ mod tests {
         }
     }
 
+    #[test]
+    fn equiv() {
+        proof! {
+            [TFL_BASIC, TFL_DERIVED],
+            0, "A <-> B", "PR",
+            0, "(A -> B) ^ (B -> A)", "Equiv 1",
+            0, "A <-> B", "Equiv 2",
+        }
+
+        proof! {
+            [TFL_BASIC, TFL_DERIVED],
+            0, "(A ^ B) <-> ~(C v D)", "PR",
+            0, "((A ^ B) -> ~(C v D)) ^ (~(C v D) -> (A ^ B))", "Equiv 1",
+            0, "(A ^ B) <-> ~(C v D)", "Equiv 2",
+        }
+
+        bad_proof! {
+            [TFL_BASIC, TFL_DERIVED],
+            [(2, CheckError::BadUsage), (4, CheckError::BadUsage)],
+            0, "A <-> B", "PR",
+            0, "(A -> B) ^ (A -> B)", "Equiv 1",
+            0, "(A -> B) ^ (B -> A)", "Equiv 1",
+            0, "B <-> A", "Equiv 3",
+        }
+
+        // Matching is over whole sentences, so nested biconditionals aren't expanded.
+        bad_proof! {
+            [TFL_BASIC, TFL_DERIVED],
+            [(2, CheckError::BadUsage)],
+            0, "C ^ (A <-> B)", "PR",
+            0, "C ^ ((A -> B) ^ (B -> A))", "Equiv 1",
+        }
+    }
+
+    #[test]
+    fn neg_bic() {
+        proof! {
+            [TFL_BASIC, TFL_DERIVED],
+            0, "~(A <-> B)", "PR",
+            0, "A <-> ~B", "NegBic 1",
+            0, "~(A <-> B)", "NegBic 2",
+        }
+
+        proof! {
+            [TFL_BASIC, TFL_DERIVED],
+            0, "~((A ^ C) <-> ~B)", "PR",
+            0, "(A ^ C) <-> ~~B", "NegBic 1",
+        }
+
+        bad_proof! {
+            [TFL_BASIC, TFL_DERIVED],
+            [(2, CheckError::BadUsage), (3, CheckError::BadUsage), (4, CheckError::BadUsage)],
+            0, "~(A <-> B)", "PR",
+            0, "~A <-> B", "NegBic 1",
+            0, "A -> ~B", "NegBic 1",
+            0, "A ^ ~B", "NegBic 1",
+        }
+    }
+
+    #[test]
+    fn neg_imp() {
+        proof! {
+            [TFL_BASIC, TFL_DERIVED],
+            0, "~(A -> B)", "PR",
+            0, "A ^ ~B", "NegImp 1",
+            0, "~(A -> B)", "NegImp 2",
+        }
+
+        bad_proof! {
+            [TFL_BASIC, TFL_DERIVED],
+            [(3, CheckError::BadUsage), (4, CheckError::BadUsage), (5, CheckError::BadUsage)],
+            0, "~(A -> B)", "PR",
+            0, "~(A <-> B)", "PR",
+            0, "~B ^ A", "NegImp 1",
+            0, "A <-> ~B", "NegImp 1",
+            0, "A ^ ~B", "NegImp 2",
+        }
+    }
+
     #[test]
     fn complex_tfl_derived() {
 
     }
 
+    #[test]
+    fn classical_axioms() {
+        proof! {
+            [TFL_BASIC, CLASSICAL_AXIOMS],
+            0, "((A -> B) -> A) -> A", "Peirce",
+            0, "((~C -> (A ^ B)) -> ~C) -> ~C", "Peirce",
+            0, "D v ~D", "ExMid",
+            0, "(A -> B) v ~(A -> B)", "ExMid",
+            0, "~~A -> A", "DblNeg",
+            0, "(~B -> B) -> B", "Clavius",
+        }
+
+        // Metavariables have to stand for the same sentence everywhere.
+        bad_proof! {
+            [TFL_BASIC, CLASSICAL_AXIOMS],
+            [
+                (1, CheckError::BadUsage),
+                (2, CheckError::BadUsage),
+                (3, CheckError::BadUsage),
+                (4, CheckError::BadUsage),
+                (5, CheckError::BadUsage),
+                (6, CheckError::BadLineCount { rule: String::from("ExMid"), expected: 0, subproofs: 0, found: 1 }),
+            ],
+            0, "((A -> B) -> A) -> B", "Peirce",
+            0, "((A -> B) -> C) -> A", "Peirce",
+            0, "A v ~B", "ExMid",
+            0, "~A v A", "ExMid",
+            0, "~A -> A", "DblNeg",
+            0, "A v ~A", "ExMid 1",
+        }
+    }
+
     #[test]
     fn necessity_intr() {
         proof! {
@@ -481,6 +2366,210 @@ mod tests {
             2, "[]", "PR",
             2, "A", "[]E 1",
         }
+
+        // An ordinary subproof within the strict subproof stays in the same world.
+        proof! {
+            [TFL_BASIC, SYSTEM_K],
+            0, "[]A", "PR",
+            1, "[]", "PR",
+            2, "B", "PR",
+            2, "A", "[]E 1",
+        }
+
+        // A necessary sentence from within the current world can't be eliminated.
+        bad_proof! {
+            [TFL_BASIC, SYSTEM_K],
+            [(4, CheckError::BadUsage)],
+            0, "A", "PR",
+            1, "[]", "PR",
+            2, "[]A", "PR",
+            2, "A", "[]E 3",
+        }
+    }
+
+    #[test]
+    fn possibility_intr() {
+        proof! {
+            [TFL_BASIC, SYSTEM_K, SYSTEM_T],
+            0, "[]A", "PR",
+            1, "[]", "PR",
+            1, "A", "[]E 1",
+            0, "<>A", "<>I 2-3",
+        }
+
+        bad_proof! {
+            [TFL_BASIC, SYSTEM_K],
+            [(4, CheckError::RuleNotEnabled { rule: String::from("⋄I"), ruleset: "System T" })],
+            0, "[]A", "PR",
+            1, "[]", "PR",
+            1, "A", "[]E 1",
+            0, "<>A", "<>I 2-3",
+        }
+
+        bad_proof! {
+            [TFL_BASIC, SYSTEM_K, SYSTEM_T],
+            [(4, CheckError::BadUsage)],
+            0, "A", "PR",
+            1, "B", "PR",
+            1, "A", "R 1",
+            0, "<>A", "<>I 2-3",
+        }
+    }
+
+    #[test]
+    fn possibility_elim() {
+        proof! {
+            [TFL_BASIC, SYSTEM_K],
+            0, "<>A", "PR",
+            0, "[](A -> B)", "PR",
+            1, "[]", "AS",
+            1, "A", "AS",
+            1, "A -> B", "[]E 2",
+            1, "B", "->E 5 4",
+            0, "<>B", "<>E 1 3-6",
+        }
+
+        // The subproof has to assume the possible sentence...
+        bad_proof! {
+            [TFL_BASIC, SYSTEM_K],
+            [(7, CheckError::BadUsage)],
+            0, "<>A", "PR",
+            0, "[](B -> A)", "PR",
+            1, "[]", "AS",
+            1, "B", "AS",
+            1, "B -> A", "[]E 2",
+            1, "A", "->E 5 4",
+            0, "<>A", "<>E 1 3-6",
+        }
+
+        // ...and reach the one concluded.
+        bad_proof! {
+            [TFL_BASIC, SYSTEM_K],
+            [(6, CheckError::BadUsage)],
+            0, "<>A", "PR",
+            0, "[](A -> B)", "PR",
+            1, "[]", "AS",
+            1, "A", "AS",
+            1, "A -> B", "[]E 2",
+            0, "<>B", "<>E 1 3-5",
+        }
+    }
+
+    #[test]
+    fn strict_subproofs() {
+        // Successive strict subproofs are each one level deep.
+        proof! {
+            [TFL_BASIC, SYSTEM_K],
+            0, "[]A", "PR",
+            1, "[]", "PR",
+            1, "A", "[]E 1",
+            0, "[]A", "[]I 2-3",
+            1, "[]", "PR",
+            1, "A", "[]E 1",
+        }
+
+        // Strict-only rules can't be used in a subproof missing its signal.
+        bad_proof! {
+            [TFL_BASIC, SYSTEM_K],
+            [(3, CheckError::StrictOutside)],
+            0, "[]A", "PR",
+            1, "B", "PR",
+            1, "A", "[]E 1",
+        }
+
+        // Strict subproofs are opened by exactly one signal.
+        assert_eq!(
+            Proof::parse([
+                (0, "[]A", "PR"),
+                (1, "[]", "PR"),
+                (1, "[]", "PR"),
+                (1, "A", "[]E 1"),
+            ]).unwrap_err(),
+            vec![(3, ParseError::ExtraNecessity)]
+        );
+
+        // Strict subproofs can't be opened at depth zero...
+        assert_eq!(
+            Proof::parse([
+                (0, "[]A", "PR"),
+                (0, "[]", "PR"),
+                (0, "A", "R 1"),
+            ]).unwrap_err(),
+            vec![(2, ParseError::ShallowNecessity)]
+        );
+
+        // ...or without increasing the depth.
+        assert_eq!(
+            Proof::parse([
+                (0, "[]A", "PR"),
+                (1, "B", "PR"),
+                (1, "[]", "PR"),
+                (1, "A", "[]E 1"),
+            ]).unwrap_err(),
+            vec![(3, ParseError::ShallowNecessity)]
+        );
+    }
+
+    #[test]
+    fn proof_records() {
+        let p = Proof::parse([
+            (0, "[]A", "PR"),
+            (1, "[]", "PR"),
+            (1, "A", "[]E 1"),
+            (0, "[]A", "[]I 2-3"),
+        ]).unwrap();
+
+        let r = p.to_records();
+
+        assert_eq!(r.len(), 4);
+        assert_eq!(
+            r[3],
+            ProofRecord {
+                line_number: 4,
+                depth: 0,
+                sentence: String::from("□A"),
+                rule: String::from("□I"),
+                cited_lines: vec![LineNumber::Many(2..=3)],
+                strict: false,
+            }
+        );
+        assert!(r[1].strict && r[2].strict);
+        assert_eq!(r[2].cited_lines, vec![LineNumber::One(1)]);
+    }
+
+    #[test]
+    fn subproofs() {
+        let p = Proof::parse([
+            (0, "A", "PR"),
+            (1, "B", "PR"),
+            (2, "C", "PR"),
+            (2, "A", "R 1"),
+            (1, "C -> A", "->I 3-4"),
+            (1, "D", "PR"),
+            (1, "D", "R 6"),
+            (0, "[]A", "PR"),
+            (1, "[]", "PR"),
+            (1, "A", "[]E 8"),
+        ]).unwrap();
+
+        let subproof = |start, end, depth, strict| SubproofRange { start, end, depth, strict };
+
+        assert_eq!(
+            p.subproofs().collect::<Vec<_>>(),
+            vec![
+                subproof(2, 5, 1, false),
+                subproof(3, 4, 2, false),
+                subproof(6, 7, 1, false),
+                subproof(9, 10, 1, true),
+            ]
+        );
+
+        assert_eq!(p.line_range(4).unwrap(), &p.lines[2..4]);
+        assert_eq!(p.line_range(5).unwrap(), &p.lines[1..5]);
+        assert_eq!(p.line_range(6).unwrap(), &p.lines[5..7]);
+        assert_eq!(p.line_range(8).unwrap(), &p.lines[..]);
+        assert!(p.line_range(0).is_none());
+        assert!(p.line_range(11).is_none());
     }
 
     #[test]
@@ -525,13 +2614,43 @@ mod tests {
 
         bad_proof! {
             [SYSTEM_T],
-            [(3, CheckError::Unavailable)],
+            [(3, CheckError::AcrossStrictBoundary { rule: String::from("RT") })],
             0, "[]A", "PR",
             1, "[]", "PR",
             1, "[]A", "RT 1",
         }
     }
 
+    #[test]
+    fn strict_reiteration() {
+        // Necessary sentences cross into a strict subproof through the modal rules...
+        proof! {
+            [TFL_BASIC, SYSTEM_K, SYSTEM_S4],
+            0, "[]A", "PR",
+            1, "[]", "PR",
+            1, "A", "[]E 1",
+            1, "[]A", "R4 1",
+            1, "A", "R 3",
+        }
+
+        // ...but not by plain reiteration, even with the □ intact.
+        bad_proof! {
+            [TFL_BASIC, SYSTEM_K, SYSTEM_S4],
+            [(3, CheckError::AcrossStrictBoundary { rule: String::from("R") })],
+            0, "[]A", "PR",
+            1, "[]", "PR",
+            1, "[]A", "R 1",
+        }
+
+        bad_proof! {
+            [TFL_BASIC, SYSTEM_K],
+            [(3, CheckError::AcrossStrictBoundary { rule: String::from("R") })],
+            0, "A", "PR",
+            1, "[]", "PR",
+            1, "A", "R 1",
+        }
+    }
+
     #[test]
     fn rule_four() {
         proof! {
@@ -549,6 +2668,16 @@ mod tests {
             2, "[]", "PR",
             2, "[]A", "R4 1",
         }
+
+        // Crossing several strict levels takes one R4 per level.
+        proof! {
+            [SYSTEM_S4],
+            0, "[]A", "PR",
+            1, "[]", "PR",
+            1, "[]A", "R4 1",
+            2, "[]", "PR",
+            2, "[]A", "R4 3",
+        }
     }
 
     #[test]
@@ -568,6 +2697,34 @@ mod tests {
             2, "[]", "PR",
             2, "~[]A", "R5 1",
         }
+
+        // As with R4, one level at a time.
+        proof! {
+            [SYSTEM_S5],
+            0, "~[]A", "PR",
+            1, "[]", "PR",
+            1, "~[]A", "R5 1",
+            2, "[]", "PR",
+            2, "~[]A", "R5 3",
+        }
+
+        // R5 only reiterates negated necessities - not necessities themselves...
+        bad_proof! {
+            [SYSTEM_S5],
+            [(3, CheckError::BadUsage)],
+            0, "[]A", "PR",
+            1, "[]", "PR",
+            1, "[]A", "R5 1",
+        }
+
+        // ...nor any other negation.
+        bad_proof! {
+            [SYSTEM_S5],
+            [(3, CheckError::BadUsage)],
+            0, "~<>A", "PR",
+            1, "[]", "PR",
+            1, "~<>A", "R5 1",
+        }
     }
 
     #[test]
@@ -615,5 +2772,32 @@ mod tests {
             0, "[]~P", "[]I 3-9",
             0, "~<>P", "MC 10",
         }
+
+        // Homework 5-5, possibility variant
+        // Prove <>(P v R) from <>P
+        proof! {
+            [TFL_BASIC, SYSTEM_K],
+            0, "<>P", "PR",
+            1, "[]", "PR",
+            1, "P", "PR",
+            1, "P v R", "vI 3",
+            0, "<>(P v R)", "<>E 1 2-4",
+        }
+
+        // Homework 5-6, possibility variant
+        // Prove <>R from <>P, [](P -> Q) and [](Q -> R)
+        proof! {
+            [TFL_BASIC, SYSTEM_K],
+            0, "<>P", "PR",
+            0, "[](P -> Q)", "PR",
+            0, "[](Q -> R)", "PR",
+            1, "[]", "PR",
+            1, "P", "PR",
+            1, "P -> Q", "[]E 2",
+            1, "Q -> R", "[]E 3",
+            1, "Q", "->E 6 5",
+            1, "R", "->E 7 8",
+            0, "<>R", "<>E 1 4-9",
+        }
     }
 }
\ No newline at end of file
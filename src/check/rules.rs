@@ -1,11 +1,32 @@
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
 use thiserror::Error;
 
 use crate::parse::*;
 
+/// How a rule compares sentences - see [`Checker::lenient_commutativity`](crate::check::Checker::lenient_commutativity).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Equality {
+    /// Sentences must be identical.
+    Exact,
+    /// The operands of either sentence's main ∧, ∨ or ↔ may be swapped (see [`Sentence::equiv_commutative`].)
+    Commutative,
+}
+
+impl Equality {
+    pub fn same(self, a: &Sentence, b: &Sentence) -> bool {
+        match self {
+            Self::Exact       => a == b,
+            Self::Commutative => a.equiv_commutative(b),
+        }
+    }
+}
+
 /// An interface for validating proof rule usages.
 /// 
 /// `Rule` is implemented on marker structs. These are then cast to trait objects (`&dyn Rule`) and placed
-/// into a map keyed by canonical rule names - so, for example, `"^E"` maps to the trait object form of [`ConjunctionElim`].
+/// into a map keyed by canonical rule names - so, for example, `"^E"` maps to the trait object form of `ConjunctionElim`.
 /// 
 /// Validating a rule usage is done by looking up the appropriate `Rule` implementation in the map and providing
 /// the [`Proof`] and [`Line`] under scrutiny to the [`validate`](Rule::validate) method, which returns either
@@ -14,7 +35,7 @@ pub trait Rule : Sync {
     /// Returns the order and type of lines uses of this rule should cite.
     fn line_ord(&self) -> &[LineNumberType];
     /// Verifies that the rule cited is used correctly.
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError>;
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError>;
 
     /// Returns whether or not the rule is only usable in a strict subproof.
     /// 
@@ -24,19 +45,30 @@ pub trait Rule : Sync {
     }
 
     /// Validate the use of this rule in justifying the provided line.
-    fn validate(&self, p: &Proof, line: &Line) -> Result<(), CheckError> {
+    fn validate(&self, p: &Proof, line: &Line, eq: Equality) -> Result<(), CheckError> {
         if self.line_ord().len() != line.cited_lines().len() {
-            return Err(CheckError::BadLineCount)
+            return Err(CheckError::BadLineCount {
+                rule: line.c.r.clone(),
+                expected: self.line_ord().len(),
+                subproofs: self.line_ord().iter().filter(|t| matches!(t, LineNumberType::Many) ).count(),
+                found: line.cited_lines().len(),
+            })
         }
 
         // Ensure expected line number types match the actual types.
-        if self
+        if let Some((position, (expected, found))) = self
             .line_ord()
             .iter()
             .zip( line.cited_lines() )
-            .any(|(e, a)| e != a) 
+            .enumerate()
+            .find(|(_, (e, a))| **e != **a) 
         {
-            return Err(CheckError::BadLineType)
+            return Err(CheckError::BadLineType {
+                rule: line.c.r.clone(),
+                position: position + 1,
+                expected: expected.clone(),
+                found: found.clone(),
+            })
         }
 
         // Ensure we are not citing ourselves or the future.
@@ -58,6 +90,25 @@ pub trait Rule : Sync {
             return Err(CheckError::BadLine)
         }
 
+        // Ensure no two citations overlap, e.g. a line and a subproof containing it.
+        // Citing the exact same line or subproof twice is fine (as in `∨E 1 2-3 2-3`.)
+        if let Some((first, second)) = overlapping(line.cited_lines()) {
+            return Err(CheckError::OverlappingCitations { first, second })
+        }
+
+        // Ensure we are not citing a subproof that the current line is still inside of.
+        if line
+            .cited_lines()
+            .iter()
+            .filter_map(|ln| match ln {
+                LineNumber::Many(r) => Some(r),
+                _ => None
+            })
+            .any(|r| p.subproofs().any(|s| s.start == *r.start() && s.contains(line.n) ))
+        {
+            return Err(CheckError::NotYetClosed)
+        }
+
         // Ensure all line ranges are citing a valid, complete subproof.
         if line
             .cited_lines()
@@ -105,67 +156,32 @@ pub trait Rule : Sync {
             return Err(CheckError::BadRange)
         }
 
-        // Accessibility indices for the line being validated.
-        let mut sentence_access = vec![false; p.len()];
-        let mut subproof_access = vec![false; p.len()];
-
-        // Precompute accessibility relative to all previous lines in the proof.
-        // (Present and future lines are by definition inaccessible.)
-        //
-        // The ceiling value is initialized to the depth of the current line.
-        let mut ceil = line.d;
-
-        // Single sentence accessibility.
-        // Step backwards through the proof from the current line.
-        for n in (1..line.n).rev() {
-            let d = p.line(n).map(|l| l.d).unwrap();
-
-            #[allow(clippy::comparison_chain)]
-            // If the line's depth is equal to the ceiling value, it is reachable.
-            if d == ceil {
-                sentence_access[n as usize - 1] = true;
-            }
-            // If the line is shallower than the ceiling value, it is reachable,
-            // but the ceiling is lowered to match.
-            else if d < ceil {
-                sentence_access[n as usize - 1] = true;
-                ceil -= 1;
-            }
+        // Ensure all cited subproofs are immediately subordinate to the current line
+        // (i.e. exactly one level deeper), rather than nested further inside another subproof.
+        if line
+            .cited_lines()
+            .iter()
+            .any(|ln| match ln {
+                LineNumber::Many(r) => p.line( *r.start() ).is_none_or(|l| l.d != line.d + 1),
+                _ => false
+            })
+        {
+            return Err(CheckError::NotSubordinate)
         }
 
-        let mut ceil = line.d;
-
-        // Subproof accessibility.
-        // Similar to above algorithm
-        for n in (1..line.n).rev() {
-            let l = p.line(n).unwrap();
-
-            // If the line is a premise one level deeper than the current ceiling,
-            // then the subproof is reachable.
-            if l.d == (ceil + 1) && l.is_premise() {
-                subproof_access[n as usize - 1] = true;
-            }
-            // If the line is shallower than the ceiling - i.e. we've left a subproof -
-            // then the ceiling is lowered to match.
-            else if l.d < ceil {
-                ceil -= 1;
-            }
-        }
+        let (sentence_access, subproof_access) = p.accessibility(line.n);
 
         // Ensure that no unavailable lines or subproofs are being cited.
-        if line
+        if let Some(n) = line
             .cited_lines()
             .iter()
-            .any(|ln| match ln {
-                LineNumber::One(n) => {
-                    !sentence_access[*n as usize - 1]
-                }
-                LineNumber::Many(r) => {
-                    !subproof_access[*r.start() as usize - 1]
-                }
+            .find_map(|ln| match ln {
+                LineNumber::One(n) if !sentence_access[*n as usize - 1] => Some(*n),
+                LineNumber::Many(r) if !subproof_access[*r.start() as usize - 1] => Some(*r.start()),
+                _ => None
             })
         {
-            return Err(CheckError::Unavailable)
+            return Err( unavailable_cause(p, line, n) )
         }
 
         // If the rule being used is only valid in a strict subproof,
@@ -175,7 +191,9 @@ pub trait Rule : Sync {
         }
         
         // If we're using a "non-strict" rule in a strict subproof,
-        // ensure it doesn't cite anything outside that subproof.
+        // ensure it doesn't cite anything outside that subproof. Sentences only cross
+        // into a strict subproof through the modal rules (□E, R4 and R5) - even
+        // necessary ones, which □E takes the □ off of and R4 keeps it on.
         if !self.strict_only() && p.strict_zones[line.n as usize - 1] {
             let mut bound = line.n - 1;
 
@@ -201,84 +219,295 @@ pub trait Rule : Sync {
                     }
                 })
             {
-                return Err(CheckError::Unavailable)
+                return Err(CheckError::AcrossStrictBoundary { rule: line.c.r.clone() })
             }
         }
 
-        self.is_right(p, line)?;
+        self.is_right(p, line, eq)?;
 
         Ok(())
     }
 }
 
+/// Find the first pair of distinct citations that share a line, if any.
+fn overlapping(cited: &[LineNumber]) -> Option<(LineNumber, LineNumber)> {
+    let span = |ln: &LineNumber| match ln {
+        LineNumber::One(n)  => *n..=*n,
+        LineNumber::Many(r) => r.clone()
+    };
+
+    cited
+        .iter()
+        .enumerate()
+        .flat_map(|(i, a)| cited[i + 1..].iter().map(move |b| (a, b)) )
+        .find(|(a, b)| {
+            let (a, b) = (span(a), span(b));
+            a != b && a.start() <= b.end() && b.start() <= a.end()
+        })
+        .map(|(a, b)| (a.clone(), b.clone()))
+}
+
 // rustc doesn't seem to count uses in default trait method impls?
 #[allow(dead_code)]
 #[derive(Debug, Error, PartialEq, Eq, Clone)]
 pub enum CheckError {
     #[error("cited a rule that does not exist or is badly formed")]
     NoSuchRule,
-    #[error("cited too few or too many lines for the specified rule")]
-    BadLineCount,
-    #[error("cited a line range where a single line was expected (or vice versa)")]
-    BadLineType,
+    #[error("cited {rule}, which isn't enabled for this proof; it's part of {ruleset}")]
+    RuleNotEnabled {
+        rule: String,
+        /// The name of the (first) ruleset the rule belongs to.
+        ruleset: &'static str,
+    },
+    #[error("{rule} expects {} but you gave {found}", describe_count(*.expected, *.subproofs))]
+    BadLineCount {
+        rule: String,
+        /// The number of citations the rule expects...
+        expected: usize,
+        /// ...of which this many are subproofs.
+        subproofs: usize,
+        found: usize,
+    },
+    #[error("{}", describe_type(.rule, *.position, .expected, .found))]
+    BadLineType {
+        rule: String,
+        /// The (one-based) position of the offending citation.
+        position: usize,
+        expected: LineNumberType,
+        found: LineNumber,
+    },
     #[error("cited a rule that was used incorrectly")]
     BadUsage,
+    #[error("cited subproofs that reach different conclusions ({first} and {second}); both must end with the same sentence")]
+    MismatchedConclusions { first: String, second: String },
     #[error("cited a current or future line, or a line that does not exist")]
     BadLine,
     #[error("cited a line range that does not correspond to a subproof")]
     BadRange,
+    #[error("cited both {first} and {second}, which overlap; a line can't be cited alongside a subproof containing it")]
+    OverlappingCitations { first: LineNumber, second: LineNumber },
     #[error("cited an unavailable line or subproof")]
     Unavailable,
+    #[error("cited a line or subproof inside a different subproof; lines can only be cited from within the subproof they appear in")]
+    InSiblingSubproof,
+    #[error("line {line} is inside subproof {start}–{end}, which has been discharged; you can cite the subproof as {start}-{end} but not its individual lines")]
+    InDischargedSubproof { line: u16, start: u16, end: u16 },
+    #[error("cited a subproof that has not been closed yet; end the subproof before citing it")]
+    NotYetClosed,
+    #[error("cited a subproof that is not immediately subordinate to the current line")]
+    NotSubordinate,
     #[error("used a strict-subproof-only rule outside of a strict subproof")]
     StrictOutside,
+    #[error("made an assumption outside of a subproof; use PR for the premises of the proof")]
+    TopLevelAssumption,
+    #[error("added a premise after the proof had already started; all premises must come first")]
+    LatePremise,
+    #[error("cited a line from outside the current subproof; reiterate it into the subproof with R first")]
+    NotReiterated,
+    #[error("used {rule} to cite a line from outside the current strict subproof; only □E, R4 and R5 can bring sentences into a strict subproof")]
+    AcrossStrictBoundary { rule: String },
 }
 
-fn check_strict_nesting(p: &Proof, s: u16, e: u16) -> Result<(), CheckError> {
-    let mut depth = 0_u16;
-    let mut nest  = 0_u16;
+impl Line {
+    /// The line number of the `n`th citation, which should be a single line.
+    pub fn cited_line(&self, n: usize) -> Result<u16, CheckError> {
+        let ln = self.cited_lines().get(n).ok_or(CheckError::BadLine)?;
+
+        ln.as_one().ok_or_else(|| CheckError::BadLineType {
+            rule: self.c.r.clone(),
+            position: n + 1,
+            expected: LineNumberType::One,
+            found: ln.clone(),
+        })
+    }
 
-    for n in s..=e {
-        let line = p.line(n).unwrap();
+    /// The sentence on the line cited by the `n`th citation.
+    pub fn cited_sentence<'p>(&self, p: &'p Proof, n: usize) -> Result<&'p Sentence, CheckError> {
+        p.line( self.cited_line(n)? )
+            .map(|l| &l.s)
+            .ok_or(CheckError::BadLine)
+    }
 
-        if line.s.is_nec_signal() {
-            nest += 1;
-        } else if line.d < depth {
-            nest = nest.saturating_sub(1);
-        }
+    /// The first and last sentences of the subproof cited by the `n`th citation.
+    pub fn cited_subproof<'p>(&self, p: &'p Proof, n: usize) -> Result<(&'p Sentence, &'p Sentence), CheckError> {
+        let ln = self.cited_lines().get(n).ok_or(CheckError::BadLine)?;
+
+        let range = ln.as_many().ok_or_else(|| CheckError::BadLineType {
+            rule: self.c.r.clone(),
+            position: n + 1,
+            expected: LineNumberType::Many,
+            found: ln.clone(),
+        })?;
+
+        let start = p.line( *range.start() ).ok_or(CheckError::BadLine)?;
+        let end = p.line( *range.end() ).ok_or(CheckError::BadLine)?;
+
+        Ok( (&start.s, &end.s) )
+    }
+}
+
+/// Describe an expected number of citations, e.g. "3 citations (1 line and 2 subproofs)".
+pub(crate) fn describe_count(expected: usize, subproofs: usize) -> String {
+    let plural = |n: usize, noun: &str| match n {
+        1 => format!("1 {noun}"),
+        n => format!("{n} {noun}s")
+    };
+
+    let lines = expected - subproofs;
+
+    match (lines, subproofs) {
+        (0, 0) => String::from("no citations"),
+        (_, 0) => plural(lines, "line"),
+        (0, _) => plural(subproofs, "subproof"),
+        (_, _) => format!(
+            "{} ({} and {})",
+            plural(expected, "citation"),
+            plural(lines, "line"),
+            plural(subproofs, "subproof")
+        )
+    }
+}
+
+/// Describe a citation of the wrong type, e.g. "∨E's 2nd citation must be a subproof range like 3-5,
+/// but you cited a single line 3".
+fn describe_type(rule: &str, position: usize, expected: &LineNumberType, found: &LineNumber) -> String {
+    let ordinal = match (position % 10, position % 100) {
+        (1, n) if n != 11 => format!("{position}st"),
+        (2, n) if n != 12 => format!("{position}nd"),
+        (3, n) if n != 13 => format!("{position}rd"),
+        _                 => format!("{position}th"),
+    };
+
+    let (expected, kind) = match expected {
+        LineNumberType::One  => ("a single line like 3", "subproof range"),
+        LineNumberType::Many => ("a subproof range like 3-5", "single line"),
+    };
+
+    format!("{rule}'s {ordinal} citation must be {expected}, but you cited a {kind} {found}")
+}
 
-        depth = line.d;
+/// Match a sentence against a schema, in which each atomic sentence is a metavariable standing for any sentence.
+/// 
+/// A metavariable has to stand for the same sentence everywhere it appears; `bindings` holds what each has been matched to so far.
+pub(crate) fn match_schema(schema: &Sentence, s: &Sentence, bindings: &mut HashMap<char, Sentence>) -> bool {
+    use Sentence::*;
+
+    match (schema, s) {
+        (Atomic(m), s) => match bindings.get(m) {
+            Some(bound) => bound == s,
+            None => {
+                bindings.insert(*m, s.clone());
+                true
+            }
+        },
+        (Signal(a), Signal(b)) => a == b,
+        (Neg(a), Neg(b)) | (Nec(a), Nec(b)) | (Pos(a), Pos(b)) => match_schema(a, b, bindings),
+        (Con(a, b), Con(c, d))
+        | (Dis(a, b), Dis(c, d))
+        | (Imp(a, b), Imp(c, d))
+        | (Bic(a, b), Bic(c, d)) => match_schema(a, c, bindings) && match_schema(b, d, bindings),
+        _ => false
     }
+}
 
-    if nest > 1 {
-        return Err(CheckError::BadUsage)
+/// Explain why line `n` is unavailable to the line being validated, based on the subproof structure.
+fn unavailable_cause(p: &Proof, line: &Line, n: u16) -> CheckError {
+    // The outermost subproof that contains the cited line, but not the current one.
+    let Some(closed) = p
+        .subproofs()
+        .filter(|s| s.contains(n) && !s.contains(line.n) )
+        .min_by_key(|s| s.depth)
+    else {
+        return CheckError::Unavailable
+    };
+
+    // If the current line is inside another subproof at the same level,
+    // then the two are in separate branches of the proof.
+    if p.subproofs().any(|s| s.depth == closed.depth && s.contains(line.n) ) {
+        CheckError::InSiblingSubproof
+    } else {
+        CheckError::InDischargedSubproof { line: n, start: closed.start, end: closed.end }
     }
+}
+
+/// Count the strict subproofs that enclose line `m` but not line `n`
+/// (i.e. the number of world boundaries between the two lines.)
+fn strict_boundaries(p: &Proof, n: u16, m: u16) -> Result<u16, CheckError> {
+    let mut ceil = p.line(m).ok_or(CheckError::BadLine)?.d;
+    let mut count = 0_u16;
+
+    // Step backwards from line `m`, tracking the depth of the innermost subproof
+    // still enclosing it. Lines deeper than the ceiling belong to closed subproofs
+    // and are skipped; a premise at the ceiling opens an enclosing subproof.
+    for k in ((n + 1)..m).rev() {
+        let l = p.line(k).ok_or(CheckError::BadLine)?;
+
+        if l.d == ceil && l.d > 0 && l.is_premise() && !p.is_strict_assumption(l) {
+            if l.s.is_nec_signal() {
+                count += 1;
+            }
 
-    Ok(())
+            ceil -= 1;
+        }
+        else if l.d < ceil {
+            ceil = l.d;
+        }
+    }
+
+    Ok(count)
 }
 
-pub struct Premise;
+pub(crate) struct Premise;
 
 impl Rule for Premise {
     fn line_ord(&self) -> &[LineNumberType] {
         &[]
     }
 
-    fn is_right(&self, _p: &Proof, _l: &Line) -> Result<(), CheckError> {
+    fn is_right(&self, p: &Proof, l: &Line, _eq: Equality) -> Result<(), CheckError> {
+        // A premise inside a subproof opens it, just like an assumption. Outside of
+        // any subproof, premises have to come before everything else - otherwise, any
+        // sentence could be "proven" by adding it as a premise partway through.
+        let late = l.d == 0 && l.c.r == "PR" && p.lines
+            .iter()
+            .take_while(|e| e.n < l.n)
+            .any(|e| e.d > 0 || e.c.r != "PR");
+
+        if late {
+            return Err(CheckError::LatePremise)
+        }
+
         Ok(())
     }
 }
 
-pub struct Reiteration;
+pub(crate) struct Assumption;
+
+impl Rule for Assumption {
+    fn line_ord(&self) -> &[LineNumberType] {
+        &[]
+    }
+
+    fn is_right(&self, _p: &Proof, l: &Line, _eq: Equality) -> Result<(), CheckError> {
+        if l.d == 0 {
+            return Err(CheckError::TopLevelAssumption)
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) struct Reiteration;
 
 impl Rule for Reiteration {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {        
-        let source = l.cited_sentence(p, 0);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {        
+        let source = l.cited_sentence(p, 0)?;
 
-        if source != &l.s {
+        if !eq.same(source, &l.s) {
             return Err(CheckError::BadUsage)
         }
 
@@ -286,22 +515,22 @@ impl Rule for Reiteration {
     }
 }
 
-pub struct ConjunctionIntr;
+pub(crate) struct ConjunctionIntr;
 
 impl Rule for ConjunctionIntr {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One, LineNumberType::One]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let s_a = l.cited_sentence(p, 0);
-        let s_b = l.cited_sentence(p, 1);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let s_a = l.cited_sentence(p, 0)?;
+        let s_b = l.cited_sentence(p, 1)?;
 
         let Sentence::Con(lhs, rhs) = &l.s else {
             return Err(CheckError::BadUsage)
         };
 
-        if (lhs == s_a || lhs == s_b) && (rhs == s_a || rhs == s_b) {
+        if (eq.same(lhs, s_a) || eq.same(lhs, s_b)) && (eq.same(rhs, s_a) || eq.same(rhs, s_b)) {
             Ok(())
         } else {
             Err(CheckError::BadUsage)
@@ -309,21 +538,21 @@ impl Rule for ConjunctionIntr {
     }
 }
 
-pub struct ConjunctionElim;
+pub(crate) struct ConjunctionElim;
 
 impl Rule for ConjunctionElim {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let source = l.cited_sentence(p, 0);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let source = l.cited_sentence(p, 0)?;
 
         let Sentence::Con(lhs, rhs) = source else {
             return Err(CheckError::BadUsage)
         };
 
-        match (lhs == l.s, rhs == l.s) {
+        match ( eq.same(lhs, &l.s), eq.same(rhs, &l.s) ) {
             (true, _) => Ok(()),
             (_, true) => Ok(()),
             _ => Err(CheckError::BadUsage)
@@ -331,21 +560,50 @@ impl Rule for ConjunctionElim {
     }
 }
 
-pub struct DisjunctionIntr;
+/// A lenient form of [`ConjunctionElim`], which can extract any conjunct reachable by
+/// descending through nested conjunctions (e.g. `B` from `A ∧ (B ∧ C)`) in one step.
+pub(crate) struct DeepConjunctionElim;
+
+impl Rule for DeepConjunctionElim {
+    fn line_ord(&self) -> &[LineNumberType] {
+        &[LineNumberType::One]
+    }
+
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        fn conjuncts<'s>(s: &'s Sentence, out: &mut Vec<&'s Sentence>) {
+            if let Sentence::Con(lhs, rhs) = s {
+                for c in [lhs, rhs] {
+                    out.push(c);
+                    conjuncts(c, out);
+                }
+            }
+        }
+
+        let mut found = vec![];
+        conjuncts(l.cited_sentence(p, 0)?, &mut found);
+
+        match found.iter().any(|c| eq.same(c, &l.s) ) {
+            true  => Ok(()),
+            false => Err(CheckError::BadUsage)
+        }
+    }
+}
+
+pub(crate) struct DisjunctionIntr;
 
 impl Rule for DisjunctionIntr {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let source = l.cited_sentence(p, 0);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let source = l.cited_sentence(p, 0)?;
 
         let Sentence::Dis(lhs, rhs) = &l.s else {
             return Err(CheckError::BadUsage)
         };
 
-        if (lhs == source) || (rhs == source) {
+        if eq.same(lhs, source) || eq.same(rhs, source) {
             Ok(())
         } else {
             Err(CheckError::BadUsage)
@@ -353,28 +611,28 @@ impl Rule for DisjunctionIntr {
     }
 }
 
-pub struct DisjunctionElim;
+pub(crate) struct DisjunctionElim;
 
 impl Rule for DisjunctionElim {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One, LineNumberType::Many, LineNumberType::Many]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let source = l.cited_sentence(p, 0);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let source = l.cited_sentence(p, 0)?;
 
         let Sentence::Dis(lhs, rhs) = source else {
             return Err(CheckError::BadUsage)
         };
 
-        let (p_1, c_1) = l.cited_subproof(p, 1);
-        let (p_2, c_2) = l.cited_subproof(p, 2);
+        let (p_1, c_1) = l.cited_subproof(p, 1)?;
+        let (p_2, c_2) = l.cited_subproof(p, 2)?;
 
-        if (*c_1 != l.s) || (*c_2 != l.s) {
+        if !eq.same(c_1, &l.s) || !eq.same(c_2, &l.s) {
             return Err(CheckError::BadUsage)
         }
 
-        if (p_1 == lhs && p_2 == rhs) || (p_1 == rhs && p_2 == lhs) {
+        if (eq.same(p_1, lhs) && eq.same(p_2, rhs)) || (eq.same(p_1, rhs) && eq.same(p_2, lhs)) {
             Ok(())
         } else {
             Err(CheckError::BadUsage)
@@ -382,21 +640,21 @@ impl Rule for DisjunctionElim {
     }
 }
 
-pub struct ConditionalIntr;
+pub(crate) struct ConditionalIntr;
 
 impl Rule for ConditionalIntr {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::Many]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let (p, c) = l.cited_subproof(p, 0);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let (p, c) = l.cited_subproof(p, 0)?;
 
         let Sentence::Imp(lhs, rhs) = &l.s else {
             return Err(CheckError::BadUsage)
         };
 
-        if lhs == p && rhs == c {
+        if eq.same(lhs, p) && eq.same(rhs, c) {
             Ok(())
         } else {
             Err(CheckError::BadUsage)
@@ -404,25 +662,25 @@ impl Rule for ConditionalIntr {
     }
 }
 
-pub struct ConditionalElim;
+pub(crate) struct ConditionalElim;
 
 impl Rule for ConditionalElim {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One, LineNumberType::One]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let s_1 = l.cited_sentence(p, 0);
-        let s_2 = l.cited_sentence(p, 1);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let s_1 = l.cited_sentence(p, 0)?;
+        let s_2 = l.cited_sentence(p, 1)?;
         
         if let Sentence::Imp(lhs, rhs) = s_1 {
-            if lhs == s_2 && rhs == l.s {
+            if eq.same(lhs, s_2) && eq.same(rhs, &l.s) {
                 return Ok(())
             }
         }
 
         if let Sentence::Imp(lhs, rhs) = s_2 {
-            if lhs == s_1 && rhs == l.s {
+            if eq.same(lhs, s_1) && eq.same(rhs, &l.s) {
                 return Ok(())
             }
         }
@@ -431,26 +689,26 @@ impl Rule for ConditionalElim {
     }
 }
 
-pub struct BiconditionalIntr;
+pub(crate) struct BiconditionalIntr;
 
 impl Rule for BiconditionalIntr {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::Many, LineNumberType::Many]
     }
     
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let (p_1, c_1) = l.cited_subproof(p, 0);
-        let (p_2, c_2) = l.cited_subproof(p, 1);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let (p_1, c_1) = l.cited_subproof(p, 0)?;
+        let (p_2, c_2) = l.cited_subproof(p, 1)?;
 
         let Sentence::Bic(lhs, rhs) = &l.s else {
             return Err(CheckError::BadUsage)
         };
 
-        if (lhs == p_1 && rhs == p_2) && (lhs == c_2 && rhs == c_1) {
+        if (eq.same(lhs, p_1) && eq.same(rhs, p_2)) && (eq.same(lhs, c_2) && eq.same(rhs, c_1)) {
             return Ok(())
         }
 
-        if (lhs == p_2 && rhs == p_1) && (lhs == c_1 && rhs == c_2) {
+        if (eq.same(lhs, p_2) && eq.same(rhs, p_1)) && (eq.same(lhs, c_1) && eq.same(rhs, c_2)) {
             return Ok(())
         }
 
@@ -458,22 +716,22 @@ impl Rule for BiconditionalIntr {
     }
 }
 
-pub struct BiconditionalElim;
+pub(crate) struct BiconditionalElim;
 
 impl Rule for BiconditionalElim {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One, LineNumberType::One]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let s_1 = l.cited_sentence(p, 0);
-        let s_2 = l.cited_sentence(p, 1);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let s_1 = l.cited_sentence(p, 0)?;
+        let s_2 = l.cited_sentence(p, 1)?;
 
         let Sentence::Bic(lhs, rhs) = s_1 else {
             return Err(CheckError::BadUsage)
         };
 
-        if (lhs == s_2 && rhs == l.s) || (rhs == s_2 && lhs == l.s) {
+        if (eq.same(lhs, s_2) && eq.same(rhs, &l.s)) || (eq.same(rhs, s_2) && eq.same(lhs, &l.s)) {
             return Ok(())
         }
 
@@ -481,22 +739,22 @@ impl Rule for BiconditionalElim {
     }
 }
 
-pub struct NegationIntr;
+pub(crate) struct NegationIntr;
 
 impl Rule for NegationIntr {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::Many]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let (p, c) = l.cited_subproof(p, 0);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let (p, c) = l.cited_subproof(p, 0)?;
 
         if !c.is_bot_signal() {
             return Err(CheckError::BadUsage)
         };
 
         if let Sentence::Neg(s) = &l.s {
-            if s == p {
+            if eq.same(s, p) {
                 return Ok(())
             }
         }
@@ -505,22 +763,22 @@ impl Rule for NegationIntr {
     }
 }
 
-pub struct NegationElim;
+pub(crate) struct NegationElim;
 
 impl Rule for NegationElim {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One, LineNumberType::One]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let s_1 = l.cited_sentence(p, 0);
-        let s_2 = l.cited_sentence(p, 1);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let s_1 = l.cited_sentence(p, 0)?;
+        let s_2 = l.cited_sentence(p, 1)?;
 
         if !l.s.is_bot_signal() {
             return Err(CheckError::BadUsage)
         };
 
-        if s_1.negated() == *s_2 || s_2.negated() == *s_1 {
+        if eq.same(&s_1.negated(), s_2) || eq.same(&s_2.negated(), s_1) {
             return Ok(())
         }
 
@@ -528,15 +786,15 @@ impl Rule for NegationElim {
     }
 }
 
-pub struct Explosion;
+pub(crate) struct Explosion;
 
 impl Rule for Explosion {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One]
     }
     
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let source = l.cited_sentence(p, 0);
+    fn is_right(&self, p: &Proof, l: &Line, _eq: Equality) -> Result<(), CheckError> {
+        let source = l.cited_sentence(p, 0)?;
 
         if !source.is_bot_signal() {
             return Err(CheckError::BadUsage)
@@ -546,15 +804,15 @@ impl Rule for Explosion {
     }
 }
 
-pub struct IndirectProof;
+pub(crate) struct IndirectProof;
 
 impl Rule for IndirectProof {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::Many]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let (p, c) = l.cited_subproof(p, 0);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let (p, c) = l.cited_subproof(p, 0)?;
 
         let Sentence::Neg(p) = p else {
             return Err(CheckError::BadUsage)
@@ -564,7 +822,7 @@ impl Rule for IndirectProof {
             return Err(CheckError::BadUsage)
         };
 
-        if p != l.s {
+        if !eq.same(p, &l.s) {
             return Err(CheckError::BadUsage)
         }
 
@@ -572,23 +830,23 @@ impl Rule for IndirectProof {
     }
 }
 
-pub struct DisjunctiveSyllogism;
+pub(crate) struct DisjunctiveSyllogism;
 
 impl Rule for DisjunctiveSyllogism {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One, LineNumberType::One]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let s_1 = l.cited_sentence(p, 0);
-        let s_2 = l.cited_sentence(p, 1);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let s_1 = l.cited_sentence(p, 0)?;
+        let s_2 = l.cited_sentence(p, 1)?;
 
         if let Sentence::Dis(lhs, rhs) = s_1 {
             let Sentence::Neg(s_2) = s_2 else {
                 return Err(CheckError::BadUsage)
             };
 
-            if (s_2 == lhs && l.s == rhs) || (s_2 == rhs && l.s == lhs) {
+            if (eq.same(s_2, lhs) && eq.same(&l.s, rhs)) || (eq.same(s_2, rhs) && eq.same(&l.s, lhs)) {
                 return Ok(())
             }
         }
@@ -598,7 +856,7 @@ impl Rule for DisjunctiveSyllogism {
                 return Err(CheckError::BadUsage)
             };
 
-            if (s_1 == lhs && l.s == rhs) || (s_1 == rhs && l.s == lhs) {
+            if (eq.same(s_1, lhs) && eq.same(&l.s, rhs)) || (eq.same(s_1, rhs) && eq.same(&l.s, lhs)) {
                 return Ok(())
             }
         }
@@ -607,16 +865,16 @@ impl Rule for DisjunctiveSyllogism {
     }
 }
 
-pub struct ModusTollens;
+pub(crate) struct ModusTollens;
 
 impl Rule for ModusTollens {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One, LineNumberType::One]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let s_1 = l.cited_sentence(p, 0);
-        let s_2 = l.cited_sentence(p, 1);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let s_1 = l.cited_sentence(p, 0)?;
+        let s_2 = l.cited_sentence(p, 1)?;
 
         let Sentence::Neg(s) = &l.s else {
             return Err(CheckError::BadUsage)
@@ -627,7 +885,7 @@ impl Rule for ModusTollens {
                 return Err(CheckError::BadUsage);
             };
 
-            if s == lhs && s_2 == rhs {
+            if eq.same(s, lhs) && eq.same(s_2, rhs) {
                 return Ok(())
             }
         }
@@ -637,7 +895,7 @@ impl Rule for ModusTollens {
                 return Err(CheckError::BadUsage);
             };
 
-            if s == lhs && s_1 == rhs {
+            if eq.same(s, lhs) && eq.same(s_1, rhs) {
                 return Ok(())
             }
         }
@@ -646,15 +904,15 @@ impl Rule for ModusTollens {
     }
 }
 
-pub struct Dne;
+pub(crate) struct Dne;
 
 impl Rule for Dne {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let s = l.cited_sentence(p, 0);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let s = l.cited_sentence(p, 0)?;
         
         let Sentence::Neg(s) = s else {
             return Err(CheckError::BadUsage)
@@ -664,7 +922,7 @@ impl Rule for Dne {
             return Err(CheckError::BadUsage)
         };
 
-        if s == l.s {
+        if eq.same(s, &l.s) {
             return Ok(())
         }
 
@@ -672,26 +930,30 @@ impl Rule for Dne {
     }
 }
 
-pub struct Lem;
+pub(crate) struct Lem;
 
 impl Rule for Lem {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::Many, LineNumberType::Many]
     }
     
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let (p_1, c_1) = l.cited_subproof(p, 0);
-        let (p_2, c_2) = l.cited_subproof(p, 1);
-
-        if c_1 != c_2 {
-            return Err(CheckError::BadUsage)
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let (p_1, c_1) = l.cited_subproof(p, 0)?;
+        let (p_2, c_2) = l.cited_subproof(p, 1)?;
+
+        if !eq.same(c_1, c_2) {
+            return Err(CheckError::MismatchedConclusions {
+                first: c_1.to_string(),
+                second: c_2.to_string(),
+            })
         }
 
-        if (p_1.negated() != *p_2) && (p_2.negated() != *p_1) {
+        // Either subproof can be the one assuming the negation.
+        if !eq.same(&p_1.negated(), p_2) && !eq.same(&p_2.negated(), p_1) {
             return Err(CheckError::BadUsage)
         }
 
-        if &l.s != c_1 {
+        if !eq.same(&l.s, c_1) {
             return Err(CheckError::BadUsage)
         }
 
@@ -699,41 +961,39 @@ impl Rule for Lem {
     }
 }
 
-pub struct DeMorgan;
+pub(crate) struct DeMorgan;
 
 impl Rule for DeMorgan {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
         // this is... something
-        match l.cited_sentence(p, 0) {
+        match l.cited_sentence(p, 0)? {
             Sentence::Neg(inner) => {
                 match &**inner {
-                    Sentence::Con(lhs, rhs) => {
-                        if l.s == Sentence::Dis( lhs.negated().box_up(), rhs.negated().box_up() ) {
+                    Sentence::Con(lhs, rhs)
+                        if eq.same(&l.s, &Sentence::Dis( lhs.negated().box_up(), rhs.negated().box_up() )) => {
                             return Ok(())
-                        }
                     },
-                    Sentence::Dis(lhs, rhs) => {
-                        if l.s == Sentence::Con( lhs.negated().box_up(), rhs.negated().box_up() ) {
+                    Sentence::Dis(lhs, rhs)
+                        if eq.same(&l.s, &Sentence::Con( lhs.negated().box_up(), rhs.negated().box_up() )) => {
                             return Ok(())
-                        }
                     },
                     _ => ()
                 }
             },
             Sentence::Con(lhs, rhs) => {
                 if let ( Sentence::Neg(lhs), Sentence::Neg(rhs) ) = (&**lhs, &**rhs) {
-                    if l.s == Sentence::Dis( lhs.clone(), rhs.clone() ).negated() {
+                    if eq.same(&l.s, &Sentence::Dis( lhs.clone(), rhs.clone() ).negated()) {
                         return Ok(())
                     }
                 }
             },
             Sentence::Dis(lhs, rhs) => {
                 if let ( Sentence::Neg(lhs), Sentence::Neg(rhs) ) = (&**lhs, &**rhs) {
-                    if l.s == Sentence::Con( lhs.clone(), rhs.clone() ).negated() {
+                    if eq.same(&l.s, &Sentence::Con( lhs.clone(), rhs.clone() ).negated()) {
                         return Ok(())
                     }
                 }
@@ -745,15 +1005,126 @@ impl Rule for DeMorgan {
     }
 }
 
-pub struct NecessityIntr;
+pub(crate) struct Equiv;
+
+impl Rule for Equiv {
+    fn line_ord(&self) -> &[LineNumberType] {
+        &[LineNumberType::One]
+    }
+
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let source = l.cited_sentence(p, 0)?;
+
+        // A ↔ B becomes (A → B) ∧ (B → A).
+        let expand = |s: &Sentence| {
+            let Sentence::Bic(lhs, rhs) = s else {
+                return None
+            };
+
+            Some(Sentence::Con(
+                Sentence::Imp( lhs.clone(), rhs.clone() ).box_up(),
+                Sentence::Imp( rhs.clone(), lhs.clone() ).box_up()
+            ))
+        };
+
+        if expand(source).is_some_and(|s| eq.same(&s, &l.s) ) || expand(&l.s).is_some_and(|s| eq.same(&s, source) ) {
+            return Ok(())
+        }
+
+        Err(CheckError::BadUsage)
+    }
+}
+
+pub(crate) struct NegBic;
+
+impl Rule for NegBic {
+    fn line_ord(&self) -> &[LineNumberType] {
+        &[LineNumberType::One]
+    }
+
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let source = l.cited_sentence(p, 0)?;
+
+        // ¬(A ↔ B) becomes A ↔ ¬B.
+        let rewrite = |s: &Sentence| {
+            let Sentence::Neg(inner) = s else {
+                return None
+            };
+
+            let Sentence::Bic(lhs, rhs) = &**inner else {
+                return None
+            };
+
+            Some( Sentence::Bic( lhs.clone(), rhs.negated().box_up() ) )
+        };
+
+        if rewrite(source).is_some_and(|s| eq.same(&s, &l.s) ) || rewrite(&l.s).is_some_and(|s| eq.same(&s, source) ) {
+            return Ok(())
+        }
+
+        Err(CheckError::BadUsage)
+    }
+}
+
+pub(crate) struct NegImp;
+
+impl Rule for NegImp {
+    fn line_ord(&self) -> &[LineNumberType] {
+        &[LineNumberType::One]
+    }
+
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let source = l.cited_sentence(p, 0)?;
+
+        // ¬(A → B) becomes A ∧ ¬B.
+        let rewrite = |s: &Sentence| {
+            let Sentence::Neg(inner) = s else {
+                return None
+            };
+
+            let Sentence::Imp(lhs, rhs) = &**inner else {
+                return None
+            };
+
+            Some( Sentence::Con( lhs.clone(), rhs.negated().box_up() ) )
+        };
+
+        if rewrite(source).is_some_and(|s| eq.same(&s, &l.s) ) || rewrite(&l.s).is_some_and(|s| eq.same(&s, source) ) {
+            return Ok(())
+        }
+
+        Err(CheckError::BadUsage)
+    }
+}
+
+/// An axiom, which can be asserted on any line that fits its schema (see [`match_schema`]) without citing anything.
+/// 
+/// The schema is parsed the first time it's needed, rather than on every check.
+pub(crate) struct Axiom(pub &'static Lazy<Sentence>);
+
+impl Rule for Axiom {
+    fn line_ord(&self) -> &[LineNumberType] {
+        &[]
+    }
+
+    fn is_right(&self, _p: &Proof, l: &Line, _eq: Equality) -> Result<(), CheckError> {
+        if match_schema(self.0, &l.s, &mut HashMap::new()) {
+            return Ok(())
+        }
+
+        Err(CheckError::BadUsage)
+    }
+}
+
+pub(crate) struct NecessityIntr;
 
 impl Rule for NecessityIntr {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::Many]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let (p, c) = l.cited_subproof(p, 0);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let (p, c) = l.cited_subproof(p, 0)?;
 
         if !p.is_nec_signal() {
             return Err(CheckError::BadUsage)
@@ -763,7 +1134,7 @@ impl Rule for NecessityIntr {
             return Err(CheckError::BadUsage)
         };
 
-        if s == c {
+        if eq.same(s, c) {
             Ok(())
         } else {
             Err(CheckError::BadUsage)
@@ -771,7 +1142,7 @@ impl Rule for NecessityIntr {
     }
 }
 
-pub struct NecessityElim;
+pub(crate) struct NecessityElim;
 
 impl Rule for NecessityElim {
     fn line_ord(&self) -> &[LineNumberType] {
@@ -782,17 +1153,22 @@ impl Rule for NecessityElim {
         true
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let n = l.cited_lines()[0].as_one();
-        let s = l.cited_sentence(p, 0);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let n = l.cited_line(0)?;
+        let s = l.cited_sentence(p, 0)?;
 
         let Sentence::Nec(s) = s else {
             return Err(CheckError::BadUsage)
         };
 
-        check_strict_nesting(p, n, l.n)?;
+        // The necessary sentence must come from the world immediately
+        // outside the current strict subproof - not from within it, or
+        // from beyond another strict subproof.
+        if strict_boundaries(p, n, l.n)? != 1 {
+            return Err(CheckError::BadUsage)
+        }
 
-        if s == l.s {
+        if eq.same(s, &l.s) {
             return Ok(())
         }
 
@@ -800,15 +1176,81 @@ impl Rule for NecessityElim {
     }
 }
 
-pub struct PossibilityDef;
+pub(crate) struct PossibilityIntr;
+
+impl Rule for PossibilityIntr {
+    fn line_ord(&self) -> &[LineNumberType] {
+        &[LineNumberType::Many]
+    }
+
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let (p, c) = l.cited_subproof(p, 0)?;
+
+        if !p.is_nec_signal() {
+            return Err(CheckError::BadUsage)
+        };
+
+        let Sentence::Pos(s) = &l.s else {
+            return Err(CheckError::BadUsage)
+        };
+
+        if eq.same(s, c) {
+            Ok(())
+        } else {
+            Err(CheckError::BadUsage)
+        }
+    }
+}
+
+pub(crate) struct PossibilityElim;
+
+impl Rule for PossibilityElim {
+    fn line_ord(&self) -> &[LineNumberType] {
+        &[LineNumberType::One, LineNumberType::Many]
+    }
+
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let source = l.cited_sentence(p, 0)?;
+        let (signal, c) = l.cited_subproof(p, 1)?;
+
+        let Sentence::Pos(a) = source else {
+            return Err(CheckError::BadUsage)
+        };
+
+        if !signal.is_nec_signal() {
+            return Err(CheckError::BadUsage)
+        };
+
+        let Some(r) = l.cited_lines().get(1).and_then(LineNumber::as_many) else {
+            return Err(CheckError::BadUsage)
+        };
+
+        // The strict subproof has to make its assumption directly after the signal.
+        let Some(assumption) = p.line(r.start() + 1).filter(|s| p.is_strict_assumption(s) ) else {
+            return Err(CheckError::BadUsage)
+        };
+
+        let Sentence::Pos(b) = &l.s else {
+            return Err(CheckError::BadUsage)
+        };
+
+        if eq.same(&assumption.s, a) && eq.same(c, b) {
+            Ok(())
+        } else {
+            Err(CheckError::BadUsage)
+        }
+    }
+}
+
+pub(crate) struct PossibilityDef;
 
 impl Rule for PossibilityDef {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {        
-        match l.cited_sentence(p, 0) {
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {        
+        match l.cited_sentence(p, 0)? {
             Sentence::Pos(inner) => {
                 let Sentence::Neg(s) = &l.s else {
                     return Err(CheckError::BadUsage)
@@ -822,7 +1264,7 @@ impl Rule for PossibilityDef {
                     return Err(CheckError::BadUsage)
                 };
 
-                if inner == s {
+                if eq.same(inner, s) {
                     return Ok(())
                 }
             },
@@ -839,7 +1281,7 @@ impl Rule for PossibilityDef {
                     return Err(CheckError::BadUsage)
                 };
 
-                if inner == s {
+                if eq.same(inner, s) {
                     return Ok(())
                 }
             }
@@ -850,16 +1292,16 @@ impl Rule for PossibilityDef {
     }
 }
 
-pub struct ModalConversion;
+pub(crate) struct ModalConversion;
 
 impl Rule for ModalConversion {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
         // love too pattern match
-        match l.cited_sentence(p, 0) {
+        match l.cited_sentence(p, 0)? {
             Sentence::Neg(inner) => {
                 match &**inner {
                     Sentence::Nec(inner) => {
@@ -871,7 +1313,7 @@ impl Rule for ModalConversion {
                             return Err(CheckError::BadUsage)
                         };
 
-                        if inner == s {
+                        if eq.same(inner, s) {
                             return Ok(())
                         }
                     },
@@ -884,7 +1326,7 @@ impl Rule for ModalConversion {
                             return Err(CheckError::BadUsage)
                         };
 
-                        if inner == s {
+                        if eq.same(inner, s) {
                             return Ok(())
                         }
                     },
@@ -904,7 +1346,7 @@ impl Rule for ModalConversion {
                     return Err(CheckError::BadUsage)
                 };
 
-                if inner == s {
+                if eq.same(inner, s) {
                     return Ok(())
                 }
             },
@@ -921,7 +1363,7 @@ impl Rule for ModalConversion {
                     return Err(CheckError::BadUsage)
                 };
                 
-                if inner == s {
+                if eq.same(inner, s) {
                     return Ok(())
                 }
             }
@@ -932,15 +1374,15 @@ impl Rule for ModalConversion {
     }
 }
 
-pub struct RT;
+pub(crate) struct RT;
 
 impl Rule for RT {
     fn line_ord(&self) -> &[LineNumberType] {
         &[LineNumberType::One]
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let s = l.cited_sentence(p, 0);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let s = l.cited_sentence(p, 0)?;
 
         let Sentence::Nec(s) = s else {
             return Err(CheckError::BadUsage)
@@ -950,7 +1392,7 @@ impl Rule for RT {
             return Err(CheckError::BadUsage)
         }
 
-        if s == l.s {
+        if eq.same(s, &l.s) {
             return Ok(())
         }
 
@@ -958,7 +1400,7 @@ impl Rule for RT {
     }
 }
 
-pub struct R4;
+pub(crate) struct R4;
 
 impl Rule for R4 {
     fn line_ord(&self) -> &[LineNumberType] {
@@ -969,13 +1411,17 @@ impl Rule for R4 {
         true
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let n = l.cited_lines()[0].as_one();
-        let s = l.cited_sentence(p, 0);
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let n = l.cited_line(0)?;
+        let s = l.cited_sentence(p, 0)?;
 
-        check_strict_nesting(p, n, l.n)?;
+        // Like the other modal rules, R4 crosses one strict boundary at a time. That's no
+        // restriction on what can be proven - transitivity comes from applying it once per level.
+        if strict_boundaries(p, n, l.n)? > 1 {
+            return Err(CheckError::BadUsage)
+        }
 
-        if s == &l.s {
+        if eq.same(s, &l.s) {
             return Ok(())
         }
 
@@ -983,7 +1429,7 @@ impl Rule for R4 {
     }
 }
 
-pub struct R5;
+pub(crate) struct R5;
 
 impl Rule for R5 {
     fn line_ord(&self) -> &[LineNumberType] {
@@ -994,22 +1440,25 @@ impl Rule for R5 {
         true
     }
 
-    fn is_right(&self, p: &Proof, l: &Line) -> Result<(), CheckError> {
-        let n = l.cited_lines()[0].as_one();
-        let s = l.cited_sentence(p, 0);
-        
-        let Sentence::Neg(s_inner) = s else {
+    fn is_right(&self, p: &Proof, l: &Line, eq: Equality) -> Result<(), CheckError> {
+        let n = l.cited_line(0)?;
+        let s = l.cited_sentence(p, 0)?;
+
+        // Only negated necessities (¬□X) can be reiterated.
+        let Sentence::Neg(inner) = s else {
             return Err(CheckError::BadUsage)
         };
 
-        let Sentence::Nec(_) = &**s_inner else {
-            dbg!();
+        if !matches!(**inner, Sentence::Nec(_)) {
             return Err(CheckError::BadUsage)
-        };
+        }
 
-        check_strict_nesting(p, n, l.n)?;
+        // One strict boundary at a time, as with R4.
+        if strict_boundaries(p, n, l.n)? > 1 {
+            return Err(CheckError::BadUsage)
+        }
 
-        if s == &l.s {
+        if eq.same(s, &l.s) {
             return Ok(())
         }
 
@@ -1,3 +1,7 @@
+use once_cell::sync::Lazy;
+
+use crate::parse::{LineNumberType, Sentence};
+
 use super::rules::*;
 
 pub const ALL_RULESETS: &[&[(&str, &dyn Rule)]] = &[
@@ -6,9 +10,173 @@ pub const ALL_RULESETS: &[&[(&str, &dyn Rule)]] = &[
     SYSTEM_K,
     SYSTEM_T,
     SYSTEM_S4,
-    SYSTEM_S5
+    SYSTEM_S5,
+    CLASSICAL_AXIOMS,
+];
+
+/// Display names for each entry of [`ALL_RULESETS`].
+pub const RULESET_NAMES: &[&str] = &[
+    "Basic TFL",
+    "Derived TFL",
+    "System K",
+    "System T",
+    "System S4",
+    "System S5",
+    "Classical Axioms",
+];
+
+/// The rulesets each entry of [`ALL_RULESETS`] directly depends on, as indices into [`ALL_RULESETS`].
+/// 
+/// Every ruleset builds on Basic TFL, and each modal system extends the one before it (S5 ⊃ S4 ⊃ T ⊃ K).
+/// The modal systems also bring Derived TFL along, so that DS, MT and the like stay available in modal proofs.
+pub const RULESET_DEPENDENCIES: &[&[usize]] = &[
+    &[],     // Basic TFL
+    &[0],    // Derived TFL
+    &[0, 1], // System K
+    &[2],    // System T
+    &[3],    // System S4
+    &[4],    // System S5
+    &[0],    // Classical Axioms
+];
+
+/// Enable the ruleset at index `i` of a set of flags, along with everything it (transitively) depends on.
+pub fn enable_ruleset(flags: &mut [bool], i: usize) {
+    flags[i] = true;
+
+    for dep in RULESET_DEPENDENCIES[i] {
+        enable_ruleset(flags, *dep);
+    }
+}
+
+/// Disable the ruleset at index `i` of a set of flags, along with everything that (transitively) depends on it.
+pub fn disable_ruleset(flags: &mut [bool], i: usize) {
+    flags[i] = false;
+
+    for (j, deps) in RULESET_DEPENDENCIES.iter().enumerate() {
+        if deps.contains(&i) && flags[j] {
+            disable_ruleset(flags, j);
+        }
+    }
+}
+
+/// The name of the first ruleset in [`ALL_RULESETS`] that contains a rule with the given ID.
+pub fn ruleset_of(id: &str) -> Option<&'static str> {
+    ALL_RULESETS
+        .iter()
+        .position(|r| r.iter().any(|(i, _)| *i == id) )
+        .map(|i| RULESET_NAMES[i])
+}
+
+/// The name and a schematic statement of every rule in [`ALL_RULESETS`], keyed by ID -
+/// along with premises, assumptions and placeholders, which every checker accepts.
+const RULE_INFO: &[(&str, &str, &str)] = &[
+    ("PR",    "Premise",                       "A premise of the argument, taken as given."),
+    ("AS",    "Assumption",                    "Opens a new subproof."),
+    ("?",     "Placeholder",                   "Accepted for now, but still needs a real justification."),
+    ("R",     "Reiteration",                   "Repeat an earlier, accessible line."),
+    ("∧I",    "Conjunction introduction",      "From A and B, conclude A ∧ B."),
+    ("∧E",    "Conjunction elimination",       "From A ∧ B, conclude either A or B."),
+    ("∨I",    "Disjunction introduction",      "From A, conclude A ∨ B (or B ∨ A.)"),
+    ("∨E",    "Disjunction elimination",       "From A ∨ B, a subproof from A to C and a subproof from B to C, conclude C."),
+    ("→I",    "Conditional introduction",      "From a subproof assuming A and reaching B, conclude A → B."),
+    ("→E",    "Conditional elimination",       "From A → B and A, conclude B."),
+    ("↔I",    "Biconditional introduction",    "From a subproof from A to B and a subproof from B to A, conclude A ↔ B."),
+    ("↔E",    "Biconditional elimination",     "From A ↔ B and either side, conclude the other side."),
+    ("¬I",    "Negation introduction",         "From a subproof assuming A and reaching ⊥, conclude ¬A."),
+    ("¬E",    "Negation elimination",          "From A and ¬A, conclude ⊥."),
+    ("⊥I",    "Contradiction introduction",    "From A and ¬A (in either order), conclude ⊥."),
+    ("IP",    "Indirect proof",                "From a subproof assuming ¬A and reaching ⊥, conclude A."),
+    ("X",     "Explosion",                     "From ⊥, conclude anything."),
+    ("DS",    "Disjunctive syllogism",         "From A ∨ B and the negation of one side, conclude the other side."),
+    ("MT",    "Modus tollens",                 "From A → B and ¬B, conclude ¬A."),
+    ("DNE",   "Double negation elimination",   "From ¬¬A, conclude A."),
+    ("LEM",   "Law of excluded middle",        "From a subproof from A to B and a subproof from ¬A to B, conclude B."),
+    ("DeM",   "De Morgan's laws",              "Swap ¬(A ∧ B) with ¬A ∨ ¬B, or ¬(A ∨ B) with ¬A ∧ ¬B."),
+    ("DEM",   "De Morgan's laws",              "Swap ¬(A ∧ B) with ¬A ∨ ¬B, or ¬(A ∨ B) with ¬A ∧ ¬B."),
+    ("Equiv", "Equivalence",                   "Swap A ↔ B with (A → B) ∧ (B → A)."),
+    ("NegBic", "Negated biconditional",        "Swap ¬(A ↔ B) with A ↔ ¬B."),
+    ("NegImp", "Negated conditional",          "Swap ¬(A → B) with A ∧ ¬B."),
+    ("□I",    "Necessity introduction",        "From a strict subproof reaching A, conclude □A."),
+    ("□E",    "Necessity elimination",         "Inside a strict subproof, from □A just outside it, conclude A."),
+    ("⋄I",    "Possibility introduction",      "From a strict subproof reaching A, conclude ⋄A."),
+    ("⋄E",    "Possibility elimination",       "From ⋄A and a strict subproof assuming A and reaching B, conclude ⋄B."),
+    ("Def⋄",  "Definition of possibility",     "Swap ⋄A with ¬□¬A."),
+    ("MC",    "Modal conversion",              "Swap ¬□A with ⋄¬A, or ¬⋄A with □¬A."),
+    ("RT",    "Rule T",                        "Outside of a strict subproof, from □A, conclude A."),
+    ("R4",    "Rule 4",                        "Inside a strict subproof, from □A outside it, conclude □A."),
+    ("R5",    "Rule 5",                        "Inside a strict subproof, from ¬□A outside it, conclude ¬□A."),
+    ("Peirce", "Peirce's law",                 "Assert ((A → B) → A) → A, citing nothing."),
+    ("ExMid", "Excluded middle",               "Assert A ∨ ¬A, citing nothing."),
+    ("DblNeg", "Double negation",              "Assert ¬¬A → A, citing nothing."),
+    ("Clavius", "Consequentia mirabilis",      "Assert (¬A → A) → A, citing nothing."),
 ];
 
+/// A short, plain-language description of the rule with the given ID, e.g.
+/// "Conditional elimination: From A → B and A, conclude B."
+pub fn describe_rule(id: &str) -> Option<String> {
+    RULE_INFO
+        .iter()
+        .find(|(i, ..)| *i == id)
+        .map(|(_, name, schema)| format!("{name}: {schema}"))
+}
+
+/// Documentation for a single rule, as listed by [`catalog`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleDoc {
+    /// The name of the ruleset the rule belongs to.
+    pub ruleset: &'static str,
+    /// The ID used to cite the rule, e.g. `→E`.
+    pub id: &'static str,
+    /// The rule's full name, e.g. "Conditional elimination".
+    pub name: &'static str,
+    /// What the rule cites, e.g. "3 citations (1 line and 2 subproofs)".
+    pub arity: String,
+    /// The order and type of lines the rule cites.
+    pub cites: Vec<LineNumberType>,
+    /// Whether the rule can only be used inside a strict subproof.
+    pub strict_only: bool,
+    /// A schematic statement of the rule, e.g. "From A → B and A, conclude B."
+    pub schema: &'static str,
+}
+
+/// List every rule in [`ALL_RULESETS`], in order, for generating documentation.
+/// 
+/// A rule missing from `RULE_INFO` is still listed, named by its ID and with an empty schema.
+pub fn catalog() -> Vec<RuleDoc> {
+    ALL_RULESETS
+        .iter()
+        .zip(RULESET_NAMES)
+        .flat_map(|(rules, ruleset)| rules.iter().map(move |(id, rule)| (*ruleset, *id, *rule)) )
+        .map(|(ruleset, id, rule)| {
+            let (name, schema) = RULE_INFO
+                .iter()
+                .find(|(i, ..)| *i == id)
+                .map(|(_, name, schema)| (*name, *schema))
+                .unwrap_or((id, ""));
+
+            let cites = rule.line_ord().to_vec();
+            let subproofs = cites.iter().filter(|t| **t == LineNumberType::Many).count();
+
+            RuleDoc {
+                ruleset,
+                id,
+                name,
+                arity: describe_count(cites.len(), subproofs),
+                cites,
+                strict_only: rule.strict_only(),
+                schema,
+            }
+        })
+        .collect()
+}
+
+/// Iterate over every rule in [`ALL_RULESETS`], paired with its ID.
+pub fn all_rules() -> impl Iterator<Item = (&'static str, &'static dyn Rule)> {
+    ALL_RULESETS
+        .iter()
+        .flat_map(|r| r.iter().copied() )
+}
+
 pub const TFL_BASIC: &[(&str, &dyn Rule)] = &[
     ("R", &Reiteration),
     ("∧I", &ConjunctionIntr),
@@ -21,6 +189,8 @@ pub const TFL_BASIC: &[(&str, &dyn Rule)] = &[
     ("↔E", &BiconditionalElim),
     ("¬I", &NegationIntr),
     ("¬E", &NegationElim),
+    // Some courses name the same step as an introduction of ⊥ instead.
+    ("⊥I", &NegationElim),
     ("IP", &IndirectProof),
     ("X", &Explosion),
 ];
@@ -32,17 +202,24 @@ pub const TFL_DERIVED: &[(&str, &dyn Rule)] = &[
     ("LEM", &Lem),
     ("DeM", &DeMorgan),
     ("DEM", &DeMorgan),
+    ("Equiv", &Equiv),
+    ("NegBic", &NegBic),
+    ("NegImp", &NegImp),
 ];
 
 pub const SYSTEM_K: &[(&str, &dyn Rule)] = &[
     ("□I", &NecessityIntr),
     ("□E", &NecessityElim),
+    ("⋄E", &PossibilityElim),
     ("Def⋄", &PossibilityDef),
     ("MC", &ModalConversion)
 ];
 
+// ⋄I lives here rather than in K, as concluding ⋄A from a strict subproof
+// is only sound when the accessibility relation is reflexive.
 pub const SYSTEM_T: &[(&str, &dyn Rule)] = &[
-    ("RT", &RT)
+    ("RT", &RT),
+    ("⋄I", &PossibilityIntr),
 ];
 
 pub const SYSTEM_S4: &[(&str, &dyn Rule)] = &[
@@ -51,4 +228,21 @@ pub const SYSTEM_S4: &[(&str, &dyn Rule)] = &[
 
 pub const SYSTEM_S5: &[(&str, &dyn Rule)] = &[
     ("R5", &R5)
-];
\ No newline at end of file
+];
+
+// Axiom schemas, in which each sentence letter can stand for any sentence.
+pub const CLASSICAL_AXIOMS: &[(&str, &dyn Rule)] = &[
+    ("Peirce", &Axiom(&PEIRCE)),
+    ("ExMid", &Axiom(&EX_MID)),
+    ("DblNeg", &Axiom(&DBL_NEG)),
+    ("Clavius", &Axiom(&CLAVIUS)),
+];
+
+static PEIRCE  : Lazy<Sentence> = Lazy::new(|| schema("((A -> B) -> A) -> A") );
+static EX_MID  : Lazy<Sentence> = Lazy::new(|| schema("A v ~A") );
+static DBL_NEG : Lazy<Sentence> = Lazy::new(|| schema("~~A -> A") );
+static CLAVIUS : Lazy<Sentence> = Lazy::new(|| schema("(~A -> A) -> A") );
+
+fn schema(i: &str) -> Sentence {
+    Sentence::parse(i).expect("Axiom schemas should be well formed")
+}
\ No newline at end of file
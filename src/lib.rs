@@ -1,7 +1,9 @@
-//! Library module. Exports certain modules for fuzz testing.
-mod check;
-mod parse;
-
-pub use parse::*;
-pub use check::*;
-pub use check::rulesets::*;
\ No newline at end of file
+//! Library module. Exposes the proof parser and checker for integrators and fuzz testing.
+//! 
+//! - [`parse`] turns raw `(depth, sentence, citation)` input into a [`Proof`](parse::Proof).
+//! - [`check`] validates a parsed proof against a configurable set of rules.
+//! - `test_support` (behind the `testing` feature) builds and checks proofs in one step.
+pub mod check;
+pub mod parse;
+#[cfg(feature = "testing")]
+pub mod test_support;
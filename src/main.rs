@@ -1,8 +1,9 @@
 // Prevents Windows from opening a terminal when the executable is started
 #![windows_subsystem = "windows"]
 
-mod check;
-mod parse;
+use deduct::check;
+use deduct::parse;
+
 mod ui;
 
 #[cfg(not(target_arch = "wasm32"))]
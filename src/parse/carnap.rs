@@ -0,0 +1,312 @@
+//! Interop with the Fitch proof syntax used by [Carnap](https://carnap.io).
+//!
+//! Carnap proofs are written one line per step, as a sentence followed by a `:`-prefixed
+//! justification (e.g. `P->Q :->I 2-3`). Subproofs are indented relative to their parent,
+//! and open with an `:AS` assumption line.
+
+use super::*;
+
+/// Carnap rule names and their Deduct equivalents. Where several Carnap spellings map to
+/// one Deduct rule, the first listed is the canonical one.
+///
+/// The modal rules (□I, □E, ⋄I, ⋄E, Def⋄, MC, RT, R4 and R5) have no equivalent here;
+/// see [`Proof::carnap_incompatible`].
+const CARNAP_RULES: &[(&str, &str)] = &[
+    ("PR", "PR"),
+    ("AS", "AS"),
+    ("R", "R"),
+    ("&I", "∧I"),
+    ("/\\I", "∧I"),
+    ("&E", "∧E"),
+    ("/\\E", "∧E"),
+    ("\\/I", "∨I"),
+    ("vI", "∨I"),
+    ("\\/E", "∨E"),
+    ("vE", "∨E"),
+    ("->I", "→I"),
+    ("->E", "→E"),
+    ("<->I", "↔I"),
+    ("<->E", "↔E"),
+    ("~I", "¬I"),
+    ("~E", "¬E"),
+    ("IP", "IP"),
+    ("X", "X"),
+    ("DS", "DS"),
+    ("MT", "MT"),
+    ("DNE", "DNE"),
+    ("LEM", "LEM"),
+    ("DeM", "DeM"),
+];
+
+impl Proof {
+    /// Parse a proof written in Carnap's Fitch syntax.
+    ///
+    /// Blank lines are ignored. Rules without a Deduct equivalent are reported as
+    /// [`ParseError::UnknownRule`].
+    pub fn from_carnap(src: &str) -> Result<Self, ParseErrors> {
+        let mut lines = vec![];
+        let mut error = vec![];
+
+        // The indentation widths of all open subproofs, starting with the top level
+        // (which is set by the first line.)
+        let mut indents: Vec<usize> = vec![];
+
+        for (n, line) in src
+            .lines()
+            .filter(|l| !l.trim().is_empty() )
+            .enumerate()
+            .map(|(n, l)| (n as u16 + 1, l) )
+        {
+            let indent = line.len() - line.trim_start().len();
+
+            match indents.last() {
+                None => indents.push(indent),
+                Some(&top) if indent > top => indents.push(indent),
+                Some(_) => {
+                    // Dedenting must land exactly on an enclosing subproof.
+                    while indents.len() > 1 && indent < indents[indents.len() - 1] {
+                        indents.pop();
+                    }
+
+                    if indent != indents[indents.len() - 1] {
+                        error.push( (n, ParseError::BadIndentation) );
+                        continue;
+                    }
+                }
+            }
+
+            let depth = indents.len() as u16 - 1;
+
+            let Some((sentence, justification)) = line.split_once(':') else {
+                error.push( (n, ParseError::EmptyCitation) );
+                continue;
+            };
+
+            let justification = justification.trim();
+            let (rule, cited) = justification
+                .split_once(char::is_whitespace)
+                .unwrap_or((justification, ""));
+
+            let Some((_, rule)) = CARNAP_RULES.iter().find(|(c, _)| *c == rule) else {
+                error.push( (n, ParseError::UnknownRule( rule.to_owned() )) );
+                continue;
+            };
+
+            lines.push((
+                depth,
+                carnap_ops( sentence.trim() ),
+                format!("{rule} {cited}")
+            ));
+        }
+
+        if !error.is_empty() {
+            return Err(error);
+        }
+
+        let lines: Vec<_> = lines
+            .iter()
+            .map(|(d, s, c)| (*d, s.as_str(), c.as_str()) )
+            .collect();
+
+        Self::parse(lines)
+    }
+
+    /// Write the proof in Carnap's Fitch syntax.
+    ///
+    /// Rules without a Carnap equivalent are written out under their Deduct ID; use
+    /// [`Proof::carnap_incompatible`] to find and warn about them.
+    pub fn to_carnap(&self) -> String {
+        let mut out = String::new();
+
+        for line in &self.lines {
+            let rule = match line.c.r.as_str() {
+                "PR" if line.d > 0 => "AS",
+                r => CARNAP_RULES
+                    .iter()
+                    .find(|(_, d)| *d == r)
+                    .map(|(c, _)| *c)
+                    .unwrap_or(r)
+            };
+
+            let cited = line
+                .cited_lines()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+
+            let line = format!(
+                "{}{} :{rule} {cited}",
+                "  ".repeat(line.d as usize),
+                carnap_sentence(&line.s)
+            );
+
+            out += line.trim_end();
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// The numbers of all lines citing a rule with no Carnap equivalent.
+    pub fn carnap_incompatible(&self) -> Vec<u16> {
+        self.lines
+            .iter()
+            .filter(|l| !CARNAP_RULES.iter().any(|(_, d)| *d == l.c.r) )
+            .map(|l| l.n)
+            .collect()
+    }
+}
+
+/// Convert Carnap-specific operator spellings into ones [`normalize_ops`] understands.
+fn carnap_ops(i: &str) -> String {
+    i
+        .replace("/\\", consts::CON)
+        .replace("\\/", consts::DIS)
+        .replace("_|_", consts::BOT)
+}
+
+/// Render a sentence using Carnap's ASCII operator spellings.
+fn carnap_sentence(s: &Sentence) -> String {
+    use consts::*;
+
+    let pairs = [
+        (CON, "/\\"),
+        (DIS, "\\/"),
+        (BIC, "<->"),
+        (IMP, "->"),
+        (NEG, "~"),
+        (BOT, "_|_"),
+        (NEC, "[]"),
+        (POS, "<>"),
+    ];
+
+    let mut out = s.to_string();
+
+    for (op, carnap) in pairs {
+        out = out.replace(op, carnap);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import() {
+        let p = Proof::from_carnap("
+            P->Q :PR
+              ~Q :AS
+                P :AS
+                Q :->E 1,3
+                _|_ :~E 2,4
+              ~P :~I 3-5
+            ~Q->~P :->I 2-6
+        ").unwrap();
+
+        let expected = Proof::parse([
+            (0, "P -> Q", "PR"),
+            (1, "~Q", "AS"),
+            (2, "P", "AS"),
+            (2, "Q", "->E 1, 3"),
+            (2, "#", "~E 2, 4"),
+            (1, "~P", "~I 3-5"),
+            (0, "~Q -> ~P", "->I 2-6"),
+        ]).unwrap();
+
+        assert_eq!(p, expected);
+    }
+
+    #[test]
+    fn import_errors() {
+        assert_eq!(
+            Proof::from_carnap("
+                P :PR
+                []P :Nec 1
+                  Q :AS
+                 Q :R 3
+                Q
+            ").unwrap_err(),
+            vec![
+                (2, ParseError::UnknownRule( String::from("Nec") )),
+                (4, ParseError::BadIndentation),
+                (5, ParseError::EmptyCitation),
+            ]
+        );
+    }
+
+    #[test]
+    fn export() {
+        let p = Proof::parse([
+            (0, "P -> Q", "PR"),
+            (1, "~Q", "AS"),
+            (2, "P", "AS"),
+            (2, "Q", "->E 1, 3"),
+            (2, "#", "~E 2, 4"),
+            (1, "~P", "~I 3-5"),
+            (0, "~Q -> ~P", "->I 2-6"),
+            (0, "(~Q -> ~P) v R", "vI 7"),
+        ]).unwrap();
+
+        let carnap = p.to_carnap();
+
+        assert_eq!(
+            carnap,
+            "P -> Q :PR\n  \
+               ~Q :AS\n    \
+               P :AS\n    \
+               Q :->E 1,3\n    \
+               _|_ :~E 2,4\n  \
+               ~P :~I 3-5\n\
+             ~Q -> ~P :->I 2-6\n\
+             (~Q -> ~P) \\/ R :\\/I 7\n"
+        );
+
+        assert_eq!(Proof::from_carnap(&carnap).unwrap(), p);
+        assert!( p.carnap_incompatible().is_empty() );
+    }
+
+    #[test]
+    fn export_modal() {
+        let p = Proof::parse([
+            (0, "[]A", "PR"),
+            (1, "[]", "PR"),
+            (1, "A", "[]E 1"),
+        ]).unwrap();
+
+        assert_eq!(p.to_carnap(), "[]A :PR\n  [] :AS\n  A :□E 1\n");
+        assert_eq!(p.carnap_incompatible(), vec![3]);
+    }
+
+    #[test]
+    fn export_subproof() {
+        let p = Proof::parse([
+            (0, "A -> B", "PR"),
+            (1, "A", "AS"),
+            (1, "B", "->E 1 2"),
+            (2, "C", "AS"),
+            (2, "B", "R 3"),
+            (1, "C -> B", "->I 4-5"),
+            (0, "A -> (C -> B)", "->I 2-6"),
+        ]).unwrap();
+
+        assert_eq!(
+            p.extract_subproof(2).unwrap().to_carnap(),
+            "  A :AS\n  \
+               B :?\n    \
+               C :AS\n    \
+               B :R 2\n  \
+               C -> B :->I 3-4\n"
+        );
+
+        assert_eq!(
+            p.extract_subproof(4).unwrap().to_carnap(),
+            "  C :AS\n  B :?\n"
+        );
+
+        assert!( p.extract_subproof(1).is_none() );
+        assert!( p.extract_subproof(3).is_none() );
+    }
+}
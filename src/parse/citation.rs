@@ -9,7 +9,7 @@ use super::normalize_ops;
 use super::ParseError;
 use super::LineRange;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LineNumber {
     One(u16),
     Many(LineRange)
@@ -17,18 +17,26 @@ pub enum LineNumber {
 
 impl LineNumber {
     pub fn parse(i: &str) -> Result<Self, ParseError> {
+        Self::parse_at(i, 0)
+    }
+
+    /// Parse a line number that starts `at` characters into a citation, for error positions.
+    fn parse_at(i: &str, at: usize) -> Result<Self, ParseError> {
         static NUM_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\d{1,}"#).unwrap() );
 
+        let at = at + i.chars().take_while(|c| c.is_whitespace() ).count();
         let i = i.trim();
 
         let extract = |iter: &mut regex::Matches| -> Result<u16, _> {
-            let v = iter
+            let m = iter
                 .next()
-                .expect("Regex should have a capture")
-                .as_str();
+                .expect("Regex should have a capture");
 
-            let Ok(v) = v.parse::<u16>() else {
-                return Err(ParseError::OversizeValue)
+            let Ok(v) = m.as_str().parse::<u16>() else {
+                return Err(ParseError::OversizeValue {
+                    token: m.as_str().to_owned(),
+                    at: at + i[..m.start()].chars().count()
+                })
             };
 
             Ok(v)
@@ -37,7 +45,7 @@ impl LineNumber {
         let mut matches = NUM_REGEX.find_iter(i);
 
         match NUM_REGEX.find_iter(i).count() {
-            0 | 3.. => Err(ParseError::BadLineNumber),
+            0 | 3.. => Err(ParseError::BadLineNumber { token: i.to_owned(), at }),
             1 => {
                 Ok(Self::One(
                     extract(&mut matches)?
@@ -48,7 +56,7 @@ impl LineNumber {
                 let e = extract(&mut matches)?;
                 
                 if e <= s {
-                    return Err(ParseError::BadLineRange)
+                    return Err(ParseError::BadLineRange { token: i.to_owned(), at })
                 }
 
                 Ok( Self::Many(s..=e) )     
@@ -56,20 +64,22 @@ impl LineNumber {
         }
     }
 
-    pub fn as_one(&self) -> u16 {
+    /// The line number, if this is a single line.
+    pub fn as_one(&self) -> Option<u16> {
         let Self::One(v) = self else {
-            panic!("Tried to interpret a line range as a single line")
+            return None
         };
 
-        *v
+        Some(*v)
     }
 
-    pub fn as_many(&self) -> RangeInclusive<u16> {
+    /// The line range, if this is a range.
+    pub fn as_many(&self) -> Option<RangeInclusive<u16>> {
         let Self::Many(r) = self else {
-            panic!("Tried to interpret a single line as a line range")
+            return None
         };
 
-        r.clone()
+        Some( r.clone() )
     }
 }
 
@@ -84,7 +94,7 @@ impl Display for LineNumber {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LineNumberType {
     One,
     Many
@@ -102,7 +112,7 @@ impl PartialEq<LineNumber> for LineNumberType {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Citation {
     pub r: String,
     pub l: Vec<LineNumber>,
@@ -110,49 +120,63 @@ pub struct Citation {
 
 impl Citation {
     pub fn parse(i: &str) -> Result<Self, ParseError> {        
-        static SEP_REGEX : Lazy<Regex> = Lazy::new(|| Regex::new(r#"[;,\s]+"#).unwrap() );
+        static TOKEN_REGEX : Lazy<Regex> = Lazy::new(|| Regex::new(r#"[^;,\s]+"#).unwrap() );
         
         if i.trim().is_empty() {
             return Err(ParseError::EmptyCitation)
         }
 
-        let i = i.trim();
-
-        let i = SEP_REGEX
-            .replace_all(&normalize_ops(i), ",")
-            .trim()
-            .to_owned();
+        // Tokenize the normalized citation in place, so that
+        // each token's position is known for error reporting.
+        let i = normalize_ops(i);
 
-        let mut pieces = i.split(',').peekable();
+        let mut pieces = TOKEN_REGEX.find_iter(&i);
 
         let Some(rule) = pieces.next() else {
             return Err(ParseError::MissingRule)
         };
 
-        if pieces.peek().is_none() {
-            return Ok(Self {
-                r: rule.trim().to_owned(),
-                l: Vec::new()
-            })
-        }
-
         let lines: Vec<_> = pieces
-            .map(LineNumber::parse)
+            .map(|m| LineNumber::parse_at(m.as_str(), i[..m.start()].chars().count()) )
             .collect::<Result<_, _>>()?;
 
         Ok(Self {
-            r: rule.trim().to_owned(),
+            r: canonical_rule( rule.as_str() ),
             l: lines,
         })
     }
 }
 
+/// Fix the casing of a (normalized) rule ID that's an operator followed by `I` or `E`,
+/// so that e.g. `^i` is read as `∧I` just like `^I` is.
+/// 
+/// `XI` is also read as `⊥I` - a lone `X` isn't normalized to `⊥`, as it's the ID of explosion.
+fn canonical_rule(r: &str) -> String {
+    use super::consts::*;
+
+    if r == "XI" {
+        return format!("{BOT}I")
+    }
+
+    let suffix = [CON, DIS, IMP, BIC, NEG, NEC, POS]
+        .iter()
+        .find_map(|op| r.strip_prefix(op) );
+
+    match suffix {
+        Some(s @ ("i" | "e")) => format!("{}{}", &r[..r.len() - 1], s.to_uppercase()),
+        _ => r.to_owned()
+    }
+}
+
 impl Display for Citation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} ", self.r)?;
+        write!(f, "{}", self.r)?;
 
-        for line in &self.l {
-            write!(f, "{line} ")?;
+        for (i, line) in self.l.iter().enumerate() {
+            match i {
+                0 => write!(f, " {line}")?,
+                _ => write!(f, ", {line}")?
+            }
         }
 
         Ok(())
@@ -179,4 +203,54 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn ascii_rules() {
+        let rule = |i| Citation::parse(i).unwrap().r;
+
+        assert_eq!(rule("^I 1 2"), "∧I");
+        assert_eq!(rule("&E 1"), "∧E");
+        assert_eq!(rule("vE 1 2-3 4-5"), "∨E");
+        assert_eq!(rule("[]E 1"), "□E");
+        assert_eq!(rule("<>I 1"), "⋄I");
+        assert_eq!(rule("Def<> 1"), "Def⋄");
+        assert_eq!(rule("<->I 1-2 3-4"), "↔I");
+        assert_eq!(rule("~I 1-2"), "¬I");
+        assert_eq!(rule("XI 1 2"), "⊥I");
+        assert_eq!(rule("X 1"), "X");
+
+        assert_eq!(rule("->e 1 2"), "→E");
+        assert_eq!(rule("ve 1 2-3 4-5"), "∨E");
+        assert_eq!(rule("[]i 1-2"), "□I");
+
+        assert_eq!(rule("Equiv 1"), "Equiv");
+        assert_eq!(rule("DeM 1"), "DeM");
+    }
+
+    #[test]
+    fn display() {
+        let canonical = |i| Citation::parse(i).unwrap().to_string();
+
+        assert_eq!(canonical("->e 1   2"), "→E 1, 2");
+        assert_eq!(canonical("vE 1;2-3 , 4-5"), "∨E 1, 2-3, 4-5");
+        assert_eq!(canonical("  PR "), "PR");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Citation::parse("->E 1 2-3-4").unwrap_err(),
+            ParseError::BadLineNumber { token: String::from("2-3-4"), at: 5 }
+        );
+
+        assert_eq!(
+            Citation::parse("  ~I 3-2").unwrap_err(),
+            ParseError::BadLineRange { token: String::from("3-2"), at: 5 }
+        );
+
+        assert_eq!(
+            Citation::parse("vE 1, 2-99999").unwrap_err(),
+            ParseError::OversizeValue { token: String::from("99999"), at: 8 }
+        );
+    }
 }
\ No newline at end of file
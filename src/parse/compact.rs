@@ -0,0 +1,175 @@
+//! A compact, single-string proof format for sharing (e.g. in chat or in a URL.)
+//!
+//! Each line is written as `depth|sentence|citation`, and lines are separated by `;` -
+//! for example, `0|A|PR; 0|B|PR; 0|A ∧ B|∧I 1, 2`. A literal `|`, `;` or `\` inside a
+//! field is escaped with a backslash.
+
+use super::*;
+
+impl Proof {
+    /// Parse a proof written in the compact format.
+    ///
+    /// Whitespace around each line is ignored, as is a trailing `;`.
+    pub fn parse_compact(src: &str) -> Result<Self, ParseErrors> {
+        let mut lines = vec![];
+        let mut error = vec![];
+
+        for (n, entry) in split_escaped(src, ';')
+            .iter()
+            .filter(|e| !e.trim().is_empty() )
+            .enumerate()
+            .map(|(n, e)| (n as u16 + 1, e.trim()) )
+        {
+            let fields = split_escaped(entry, '|');
+
+            let [depth, sentence, citation] = fields.as_slice() else {
+                error.push( (n, ParseError::BadCompactLine( entry.to_owned() )) );
+                continue;
+            };
+
+            let Ok(depth) = depth.trim().parse::<u16>() else {
+                error.push( (n, ParseError::BadCompactLine( entry.to_owned() )) );
+                continue;
+            };
+
+            lines.push( (depth, unescape(sentence), unescape(citation)) );
+        }
+
+        if !error.is_empty() {
+            return Err(error);
+        }
+
+        let lines: Vec<_> = lines
+            .iter()
+            .map(|(d, s, c)| (*d, s.as_str(), c.as_str()) )
+            .collect();
+
+        Self::parse(lines)
+    }
+
+    /// Write the proof in the compact format.
+    pub fn to_compact(&self) -> String {
+        self.lines
+            .iter()
+            .map(|l| format!(
+                "{}|{}|{}",
+                l.d,
+                escape( &l.s.to_string() ),
+                escape( &l.c.to_string() )
+            ))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// Escape the compact format's delimiters (and the escape character itself.)
+fn escape(i: &str) -> String {
+    let mut out = String::with_capacity( i.len() );
+
+    for c in i.chars() {
+        if matches!(c, '\\' | '|' | ';') {
+            out.push('\\');
+        }
+
+        out.push(c);
+    }
+
+    out
+}
+
+/// Undo [`escape`].
+fn unescape(i: &str) -> String {
+    let mut out = String::with_capacity( i.len() );
+    let mut chars = i.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend( chars.next() ),
+            c    => out.push(c)
+        }
+    }
+
+    out
+}
+
+/// Split on every `sep` that isn't escaped, leaving the pieces themselves escaped.
+fn split_escaped(i: &str, sep: char) -> Vec<&str> {
+    let mut out = vec![];
+    let mut start = 0;
+    let mut escaped = false;
+
+    for (p, c) in i.char_indices() {
+        match c {
+            _ if escaped  => escaped = false,
+            '\\'          => escaped = true,
+            c if c == sep => {
+                out.push( &i[start..p] );
+                start = p + c.len_utf8();
+            }
+            _ => ()
+        }
+    }
+
+    out.push( &i[start..] );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let p = Proof::parse([
+            (0, "P -> Q", "PR"),
+            (1, "~Q", "AS"),
+            (2, "P", "AS"),
+            (2, "Q", "->E 1, 3"),
+            (2, "#", "~E 2, 4"),
+            (1, "~P", "~I 3-5"),
+            (0, "~Q -> ~P", "->I 2-6"),
+        ]).unwrap();
+
+        let compact = p.to_compact();
+
+        assert_eq!(
+            compact,
+            "0|P → Q|PR; 1|¬Q|AS; 2|P|AS; 2|Q|→E 1, 3; 2|⊥|¬E 2, 4; 1|¬P|¬I 3-5; 0|¬Q → ¬P|→I 2-6"
+        );
+
+        assert_eq!( Proof::parse_compact(&compact), Ok(p) );
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Proof::parse_compact("0|A|PR; 0|B|PR; 0|A^B|^I 1 2;"),
+            Proof::parse([
+                (0, "A", "PR"),
+                (0, "B", "PR"),
+                (0, "A ^ B", "^I 1, 2"),
+            ])
+        );
+
+        assert_eq!(
+            Proof::parse_compact("0|A|PR; A|R 1; x|A|R 1").unwrap_err(),
+            vec![
+                (2, ParseError::BadCompactLine( String::from("A|R 1") )),
+                (3, ParseError::BadCompactLine( String::from("x|A|R 1") )),
+            ]
+        );
+    }
+
+    #[test]
+    fn escaping() {
+        let field = "A | B; C \\ D";
+
+        assert_eq!( escape(field), "A \\| B\\; C \\\\ D" );
+        assert_eq!( unescape( &escape(field) ), field );
+
+        let entry = format!("0|{}|PR", escape(field));
+
+        assert_eq!( split_escaped(&entry, ';'), vec![entry.as_str()] );
+        assert_eq!( split_escaped(&entry, '|').len(), 3 );
+    }
+}
@@ -0,0 +1,189 @@
+//! The citation structure of a proof, for visualization.
+
+use std::fmt::Write;
+
+use super::*;
+
+/// Something a line can depend on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dependency {
+    /// A single line.
+    Line(u16),
+    /// A whole subproof, spanning the given lines.
+    Subproof(LineRange),
+}
+
+/// A single line in a [`DependencyGraph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyNode {
+    pub line: u16,
+    pub sentence: String,
+    /// Whether the line is a premise or assumption (and so depends on nothing.)
+    pub root: bool,
+    /// The lines and subproofs the line actually cites.
+    pub cites: Vec<Dependency>,
+    /// The lines and subproofs the line could cite.
+    pub available: Vec<Dependency>,
+}
+
+/// The lines of a proof, and the citations between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyGraph {
+    pub nodes: Vec<DependencyNode>,
+    /// The last line outside of any subproof, if any.
+    pub conclusion: Option<u16>,
+}
+
+impl Proof {
+    /// Build the graph of which lines and subproofs each line cites (and could cite.)
+    ///
+    /// Cited line ranges are expanded to the full subproof starting at the same line, where there is one.
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        let subproofs: Vec<_> = self.subproofs().collect();
+
+        let subproof = |start: u16, fallback: LineRange| {
+            subproofs
+                .iter()
+                .find(|s| s.start == start)
+                .map(|s| s.start..=s.end)
+                .unwrap_or(fallback)
+        };
+
+        let nodes = self.lines
+            .iter()
+            .map(|l| {
+                let cites = l
+                    .cited_lines()
+                    .iter()
+                    .map(|ln| match ln {
+                        LineNumber::One(n)  => Dependency::Line(*n),
+                        LineNumber::Many(r) => Dependency::Subproof( subproof(*r.start(), r.clone()) )
+                    })
+                    .collect();
+
+                let (sentences, subs) = self.accessibility(l.n);
+
+                let available = (1..l.n)
+                    .filter(|n| sentences[*n as usize - 1] )
+                    .map(Dependency::Line)
+                    .chain(
+                        (1..l.n)
+                            .filter(|n| subs[*n as usize - 1] )
+                            .map(|n| Dependency::Subproof( subproof(n, n..=n) ))
+                    )
+                    .collect();
+
+                DependencyNode {
+                    line: l.n,
+                    sentence: l.s.to_string(),
+                    root: l.is_premise(),
+                    cites,
+                    available,
+                }
+            })
+            .collect();
+
+        DependencyGraph {
+            nodes,
+            conclusion: self.lines.iter().rev().find(|l| l.d == 0).map(|l| l.n),
+        }
+    }
+}
+
+impl DependencyGraph {
+    /// Render the graph's citations in Graphviz's DOT language.
+    ///
+    /// Roots are drawn as boxes and the conclusion with a double outline.
+    /// Edges run from each cited line (or subproof, by its first line) to the line citing it.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph proof {\n");
+
+        for node in &self.nodes {
+            let mut attrs = format!("label={:?}", format!("{}. {}", node.line, node.sentence));
+
+            if node.root {
+                attrs += ", shape=box";
+            }
+
+            if self.conclusion == Some(node.line) {
+                attrs += ", peripheries=2";
+            }
+
+            let _ = writeln!(out, "    {} [{attrs}];", node.line);
+        }
+
+        for node in &self.nodes {
+            for dep in &node.cites {
+                let _ = match dep {
+                    Dependency::Line(n) => writeln!(out, "    {n} -> {};", node.line),
+                    Dependency::Subproof(r) => writeln!(
+                        out,
+                        "    {} -> {} [style=dashed, label={:?}];",
+                        r.start(),
+                        node.line,
+                        format!("{}-{}", r.start(), r.end())
+                    )
+                };
+            }
+        }
+
+        out.push('}');
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dependency_graph() {
+        let p = Proof::parse([
+            (0, "A", "PR"),
+            (1, "B", "AS"),
+            (1, "A", "R 1"),
+            (0, "B -> A", "->I 2-3"),
+            (0, "A ^ (B -> A)", "^I 1 4"),
+        ]).unwrap();
+
+        let g = p.dependency_graph();
+
+        assert_eq!(g.conclusion, Some(5));
+        assert_eq!(
+            g.nodes.iter().filter(|n| n.root ).map(|n| n.line).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+
+        assert_eq!(g.nodes[3].cites, vec![Dependency::Subproof(2..=3)]);
+        assert_eq!(g.nodes[4].cites, vec![Dependency::Line(1), Dependency::Line(4)]);
+
+        assert_eq!(
+            g.nodes[2].available,
+            vec![Dependency::Line(1), Dependency::Line(2)]
+        );
+
+        assert_eq!(
+            g.nodes[4].available,
+            vec![
+                Dependency::Line(1),
+                Dependency::Line(4),
+                Dependency::Subproof(2..=3)
+            ]
+        );
+
+        assert_eq!(
+            g.to_dot(),
+            "digraph proof {\n    \
+                1 [label=\"1. A\", shape=box];\n    \
+                2 [label=\"2. B\", shape=box];\n    \
+                3 [label=\"3. A\"];\n    \
+                4 [label=\"4. B → A\"];\n    \
+                5 [label=\"5. A ∧ (B → A)\", peripheries=2];\n    \
+                1 -> 3;\n    \
+                2 -> 4 [style=dashed, label=\"2-3\"];\n    \
+                1 -> 5;\n    \
+                4 -> 5;\n\
+            }"
+        );
+    }
+}
@@ -4,7 +4,10 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 use thiserror::Error;
 
+mod carnap;
 mod citation;
+mod compact;
+mod graph;
 mod sentence;
 
 mod consts {
@@ -18,8 +21,11 @@ mod consts {
     pub const POS: &str = "⋄";
 }
 
-pub use sentence::Sentence;
+pub use sentence::{Sentence, Alphabet};
+#[cfg(test)]
+pub(crate) use sentence::arb_sentence;
 pub use citation::{Citation, LineNumber, LineNumberType};
+pub use graph::{Dependency, DependencyGraph, DependencyNode};
 
 pub type LineRange   = RangeInclusive<u16>;
 pub type ParseErrors = Vec<(u16, ParseError)>;
@@ -32,26 +38,53 @@ pub enum ParseError {
     UnbalancedParentheses,
     #[error("encountered invalid character(s) {0:?}")]
     InvalidCharacter(Vec<String>),
-    #[error("too many operators or too few parentheses to disambiguate")]
-    Ambiguous,
+    #[error("ambiguous between {first} and {second}; add parentheses to disambiguate")]
+    Ambiguous {
+        first: char,
+        second: char,
+        /// The character offset of the second operator in the (normalized) sentence.
+        at: usize,
+    },
     #[error("missing connective/operator or misplaced parentheses")]
     MissingOp,
     #[error("misuse of unary operator internally in sentence")]
     BadUnary,
-    #[error("misuse of contradiction symbol internally in sentence")]
+    #[error("misuse of contradiction symbol (⊥) internally in sentence; it can only stand on its own")]
     BadContradiction,
     #[error("misuse of necessity symbol in a non-premise context")]
     BadNecessity,
+    #[error("strict subproof opened with more than one necessity symbol")]
+    ExtraNecessity,
+    #[error("necessity symbol must open a new, deeper subproof")]
+    ShallowNecessity,
     #[error("empty citation")]
     EmptyCitation,
     #[error("citation does not cite a rule")]
     MissingRule,
-    #[error("malformed line number")]
-    BadLineNumber,
-    #[error("cited a line range with the end at or before the start")]
-    BadLineRange,
-    #[error("line number too large")]
-    OversizeValue,
+    #[error("malformed line number {token:?}")]
+    BadLineNumber {
+        token: String,
+        /// The character offset of the token in the (normalized) citation.
+        at: usize,
+    },
+    #[error("cited a line range {token:?} with the end at or before the start")]
+    BadLineRange {
+        token: String,
+        /// The character offset of the token in the (normalized) citation.
+        at: usize,
+    },
+    #[error("line number {token:?} too large")]
+    OversizeValue {
+        token: String,
+        /// The character offset of the token in the (normalized) citation.
+        at: usize,
+    },
+    #[error("rule {0:?} has no Deduct equivalent")]
+    UnknownRule(String),
+    #[error("indentation does not match any open subproof")]
+    BadIndentation,
+    #[error("malformed compact line {0:?}; expected depth|sentence|citation")]
+    BadCompactLine(String),
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -67,34 +100,62 @@ impl Line {
         self.c.l.as_slice()
     }
 
+    /// Whether the line is a premise or an assumption (i.e. it opens a subproof when `d > 0`.)
+    /// 
+    /// `PR` is still accepted in place of `AS` within subproofs.
     pub fn is_premise(&self) -> bool {
-        self.c.r == "PR"
-    }
-
-    pub fn cited_sentence<'p>(&self, p: &'p Proof, n: usize) -> &'p Sentence {
-        &p.line( self.cited_lines()[n].as_one() )
-            .expect("Cited sentence should be in bounds")
-            .s
+        self.c.r == "PR" || self.c.r == "AS"
     }
+}
 
-    pub fn cited_subproof<'p>(&self, p: &'p Proof, n: usize) -> (&'p Sentence, &'p Sentence) {
-        let range = self.cited_lines()[n].as_many();
+/// The span of a single subproof within a proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubproofRange {
+    pub start: u16,
+    pub end: u16,
+    pub depth: u16,
+    pub strict: bool,
+}
 
-        (
-            &p.line( *range.start() ).expect("Cited sentence should be in bounds").s,
-            &p.line( *range.end() ).expect("Cited sentence should be in bounds").s
-        )
+impl SubproofRange {
+    pub fn contains(&self, n: u16) -> bool {
+        (self.start..=self.end).contains(&n)
     }
 }
 
+/// A flat, self-contained view of a single proof line, for interop with external tools.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofRecord {
+    pub line_number: u16,
+    pub depth: u16,
+    pub sentence: String,
+    pub rule: String,
+    pub cited_lines: Vec<LineNumber>,
+    pub strict: bool,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Proof {
     pub strict_zones: Vec<bool>,
     pub lines: Vec<Line>,
+    /// The conclusion the proof is meant to reach, if one has been declared with [`Proof::with_goal`].
+    /// 
+    /// Not to be confused with [`Proof::conclusion`], which is wherever the proof actually ends up.
+    pub goal: Option<Sentence>,
+    /// Every subproof, in order of their first line (worked out once, up front.)
+    pub(crate) subproof_ranges: Vec<SubproofRange>,
 }
 
 impl Proof {
     pub fn parse<'a, I>(i: I) -> Result<Self, ParseErrors> 
+    where
+        I: AsRef<[(u16, &'a str, &'a str)]>
+    {
+        Self::parse_with(i, Alphabet::Uppercase)
+    }
+
+    /// Parse a proof whose atomic sentences are drawn from the given alphabet.
+    pub fn parse_with<'a, I>(i: I, alphabet: Alphabet) -> Result<Self, ParseErrors> 
     where
         I: AsRef<[(u16, &'a str, &'a str)]>
     {
@@ -110,69 +171,264 @@ impl Proof {
         {
             let (depth, sentence, citation) = l;
 
-            let s = Sentence::parse(sentence);
+            let s = Sentence::parse_with(sentence, alphabet);
             let c = Citation::parse(citation);
 
-            if s.is_ok() && c.is_ok() {
-                let s = s.unwrap();
-                let c = c.unwrap();
+            match (s, c) {
+                (Ok(s), Ok(c)) => {
+                    // Ensure necessity signal is only used in a premise context.
+                    if s.is_nec_signal() && c.r != "PR" && c.r != "AS" {
+                        error.push( (i as u16, ParseError::BadNecessity) );
+                        continue;
+                    }
+
+                    lines.push(Line {
+                        s,
+                        c,
+                        n: i as u16,
+                        d: *depth,
+                    })
+                }
+                (s, c) => {
+                    if let Err(e) = s {
+                        error.push( (i as u16, e) )
+                    };
+
+                    if let Err(e) = c {
+                        error.push( (i as u16, e) );
+                    }
+                }
+            }
+        }
+
+        if !error.is_empty() {
+            return Err(error);
+        }
+
+        // Ensure each strict subproof is opened by exactly one necessity signal,
+        // and that the signal always opens a new, deeper subproof.
+        let mut prev: Option<&Line> = None;
 
-                // Ensure necessity signal is only used in a premise context.
-                if s.is_nec_signal() && c.r != "PR" {
-                    error.push( (i as u16, ParseError::BadNecessity) );
-                    continue;
+        for line in &lines {
+            if line.s.is_nec_signal() {
+                let prev_d = prev.map(|l| l.d).unwrap_or(0);
+
+                if prev.is_some_and(|l| l.s.is_nec_signal() && l.d == line.d) {
+                    error.push( (line.n, ParseError::ExtraNecessity) );
+                }
+                else if line.d == 0 || line.d <= prev_d {
+                    error.push( (line.n, ParseError::ShallowNecessity) );
                 }
+            }
+
+            prev = Some(line);
+        }
 
-                lines.push(Line {
-                    s,
+        if !error.is_empty() {
+            return Err(error);
+        }
+
+        Ok( Self::from_lines(lines) )
+    }
+
+    /// Declare the conclusion this proof is meant to reach, so that it can be
+    /// [evaluated](crate::check::Checker::evaluate_goal) without any outside state.
+    pub fn with_goal(mut self, goal: Sentence) -> Self {
+        self.goal = Some(goal);
+        self
+    }
+
+    /// Assemble a proof from already-parsed lines, working out its strict zones.
+    fn from_lines(lines: Vec<Line>) -> Self {
+        let mut proof = Self { lines, strict_zones: vec![], goal: None, subproof_ranges: vec![] };
+
+        proof.subproof_ranges = proof.find_subproofs();
+        proof.strict_zones = vec![false; proof.lines.len()];
+
+        for s in proof.subproof_ranges.iter().filter(|s| s.strict) {
+            proof.strict_zones[s.start as usize - 1..s.end as usize].fill(true);
+        }
+
+        proof
+    }
+
+    /// Copy the subproof opened on line `n` out into a proof of its own, renumbered to start
+    /// from line 1 (and with the subproof itself at depth 1.)
+    /// 
+    /// Citations of any lines outside the subproof can't be carried over, so they are
+    /// replaced with a placeholder (`?`) citation. Returns `None` if no subproof opens on line `n`.
+    pub fn extract_subproof(&self, n: u16) -> Option<Self> {
+        let range = self.subproofs().find(|s| s.start == n)?;
+        let shift = |m: u16| m - range.start + 1;
+
+        let lines = self.lines[range.start as usize - 1..range.end as usize]
+            .iter()
+            .map(|l| {
+                let inside = l.cited_lines().iter().all(|ln| match ln {
+                    LineNumber::One(m)  => range.contains(*m),
+                    LineNumber::Many(r) => range.contains(*r.start()) && range.contains(*r.end()),
+                });
+
+                let c = match inside {
+                    true => Citation {
+                        r: l.c.r.clone(),
+                        l: l.cited_lines()
+                            .iter()
+                            .map(|ln| match ln {
+                                LineNumber::One(m)  => LineNumber::One( shift(*m) ),
+                                LineNumber::Many(r) => LineNumber::Many( shift(*r.start())..=shift(*r.end()) ),
+                            })
+                            .collect()
+                    },
+                    false => Citation { r: String::from("?"), l: vec![] }
+                };
+
+                Line {
+                    s: l.s.clone(),
                     c,
-                    n: i as u16,
-                    d: *depth,
+                    n: shift(l.n),
+                    d: l.d - range.depth + 1,
+                }
+            })
+            .collect();
+
+        Some( Self::from_lines(lines) )
+    }
+
+    /// Iterate over every subproof, in order of their first line.
+    pub fn subproofs(&self) -> impl Iterator<Item = SubproofRange> + '_ {
+        self.subproof_ranges.iter().cloned()
+    }
+
+    fn find_subproofs(&self) -> Vec<SubproofRange> {
+        let mut open: Vec<SubproofRange> = vec![];
+        let mut done = vec![];
+
+        for line in &self.lines {
+            // Close any subproofs we've left - including the previous
+            // subproof at this depth, if this line opens a sibling.
+            while let Some(s) = open.last() {
+                if s.depth > line.d || (s.depth == line.d && line.is_premise() && !self.is_strict_assumption(line)) {
+                    done.extend( open.pop() );
+                } else {
+                    break;
+                }
+            }
+
+            if line.is_premise() && line.d > 0 && !self.is_strict_assumption(line) {
+                open.push(SubproofRange {
+                    start: line.n,
+                    end: line.n,
+                    depth: line.d,
+                    strict: line.s.is_nec_signal(),
                 })
             }
-            else {
-                if let Err(e) = s {
-                    error.push( (i as u16, e) )
-                };
 
-                if let Err(e) = c {
-                    error.push( (i as u16, e) );
-                }
+            for s in &mut open {
+                s.end = line.n;
             }
         }
 
-        if !error.is_empty() {
-            return Err(error);
+        done.extend(open);
+        done.sort_by_key(|s| s.start);
+        done
+    }
+
+    /// Whether `l` is the assumption made directly after a strict subproof's necessity signal,
+    /// which belongs to that subproof rather than opening a sibling of its own.
+    pub fn is_strict_assumption(&self, l: &Line) -> bool {
+        l.is_premise() && !l.s.is_nec_signal() && l.n > 1 && self
+            .line(l.n - 1)
+            .is_some_and(|prev| prev.s.is_nec_signal() && prev.d == l.d)
+    }
+
+    /// The lines of the innermost subproof containing line `n`, or the whole proof
+    /// if it isn't in a subproof.
+    pub fn line_range(&self, n: u16) -> Option<&[Line]> {
+        self.line(n)?;
+
+        let range = self
+            .subproofs()
+            .filter(|s| s.contains(n) )
+            .max_by_key(|s| s.depth)
+            .map(|s| (s.start as usize - 1)..(s.end as usize))
+            .unwrap_or(0..self.lines.len());
+
+        Some(&self.lines[range])
+    }
+
+    /// Compute which lines and subproofs line `n` can (structurally) cite.
+    /// 
+    /// Returns two sets of flags, indexed by line number minus one: whether each line's sentence is
+    /// available, and whether the subproof opened by each line is available. Present and future
+    /// lines are never available. Strict subproof restrictions are not taken into account.
+    pub fn accessibility(&self, n: u16) -> (Vec<bool>, Vec<bool>) {
+        let mut sentence_access = vec![false; self.len()];
+        let mut subproof_access = vec![false; self.len()];
+
+        let Some(line) = self.line(n) else {
+            return (sentence_access, subproof_access)
+        };
+
+        // The ceiling value is initialized to the depth of the current line.
+        let mut ceil = line.d;
+
+        // Single sentence accessibility.
+        // Step backwards through the proof from the current line.
+        for n in (1..line.n).rev() {
+            let l = self.line(n).unwrap();
+
+            // If the line's depth is at or below the ceiling value, it is reachable,
+            // and the ceiling is lowered to match.
+            if l.d <= ceil {
+                sentence_access[n as usize - 1] = true;
+                ceil = l.d;
+
+                // Passing the assumption that opened the subproof we're in takes us out
+                // into its parent - so any (sibling) subproofs before it are out of reach.
+                if l.is_premise() && ceil > 0 && !self.is_strict_assumption(l) {
+                    ceil -= 1;
+                }
+            }
         }
 
-        let mut depth = 0_u16;
-        let mut nest  = 0_u16;
-        let mut zones   = vec![false; lines.len()];
+        let mut ceil = line.d;
 
-        for (n, line) in lines.iter().enumerate() {
-            if line.s.is_nec_signal() {
-                nest += 1;
-            } else if line.d < depth {
-                nest = nest.saturating_sub(1);
+        // Subproof accessibility.
+        // Similar to above algorithm
+        for n in (1..line.n).rev() {
+            let l = self.line(n).unwrap();
+
+            // If the line is a premise one level deeper than the current ceiling,
+            // then the subproof is reachable.
+            if l.d == (ceil + 1) && l.is_premise() && !self.is_strict_assumption(l) {
+                subproof_access[n as usize - 1] = true;
+                continue;
             }
 
-            if nest > 0 {
-                zones[n] = true;
+            // If the line is shallower than the ceiling - i.e. we've left a subproof -
+            // then the ceiling is lowered to match.
+            if l.d < ceil {
+                ceil = l.d;
             }
 
-            depth = line.d;
+            // As above, passing the assumption that opened the subproof we're in
+            // takes us out into its parent.
+            if l.d == ceil && l.is_premise() && ceil > 0 && !self.is_strict_assumption(l) {
+                ceil -= 1;
+            }
         }
 
-        Ok(Self { lines, strict_zones: zones })
+        (sentence_access, subproof_access)
     }
-    
+
     #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         self.lines.len()
     }
 
     pub fn line(&self, n: u16) -> Option<&Line> {
-        self.lines.get(n as usize - 1)
+        self.lines.get( (n as usize).checked_sub(1)? )
     }
 
     pub fn reached_conclusion(&self, conclusion: &str) -> bool {
@@ -180,13 +436,73 @@ impl Proof {
             return false;
         };
 
+        self.reaches(&s)
+    }
+
+    /// Whether any line at depth zero is the given sentence.
+    pub fn reaches(&self, s: &Sentence) -> bool {
         for line in self.lines.iter().filter(|l| l.d == 0) {
-            if line.s == s { return true }
+            if line.s == *s { return true }
         }
 
         false
     }
 
+    /// The sentences introduced as premises outside of any subproof.
+    /// 
+    /// Premises that come after other lines are included, even though the checker rejects them.
+    pub fn premises(&self) -> impl Iterator<Item = &Sentence> {
+        self.lines
+            .iter()
+            .filter(|l| l.d == 0 && l.is_premise() )
+            .map(|l| &l.s)
+    }
+
+    /// The sentence on the last line outside of any subproof, if any.
+    pub fn conclusion(&self) -> Option<&Sentence> {
+        self.lines
+            .iter()
+            .rev()
+            .find(|l| l.d == 0)
+            .map(|l| &l.s)
+    }
+
+    /// Flatten the proof into one record per line.
+    pub fn to_records(&self) -> Vec<ProofRecord> {
+        self.lines
+            .iter()
+            .zip(&self.strict_zones)
+            .map(|(l, strict)| ProofRecord {
+                line_number: l.n,
+                depth: l.d,
+                sentence: l.s.to_string(),
+                rule: l.c.r.clone(),
+                cited_lines: l.c.l.clone(),
+                strict: *strict,
+            })
+            .collect()
+    }
+
+    /// Whether every line is justified by one of the `allowed` rule IDs.
+    /// 
+    /// Premises and assumptions are always allowed.
+    pub fn uses_only(&self, allowed: &[&str]) -> bool {
+        self.lines
+            .iter()
+            .all(|l| l.is_premise() || allowed.contains(&l.c.r.as_str()) )
+    }
+
+    /// Whether the proof sticks to the introduction and elimination rules of
+    /// [basic TFL](crate::check::rulesets::TFL_BASIC), avoiding derived and replacement rules.
+    pub fn is_pure_natural_deduction(&self) -> bool {
+        let basic: Vec<_> = crate::check::rulesets::TFL_BASIC
+            .iter()
+            .map(|(id, _)| *id)
+            .collect();
+
+        self.uses_only(&basic)
+    }
+
     pub fn contains_placeholders(&self) -> bool {
         for line in &self.lines {
             if line.c.r.contains('?') { return true }
@@ -198,34 +514,57 @@ impl Proof {
 
 /// Normalize operator shorthands in a given string.
 pub fn normalize_ops(i: &str) -> String {
-    use std::ops::Deref;
+    normalize(i, false)
+}
+
+/// Whether `i` ends with (at least) two letters other than `v`, the last of which is lowercase.
+fn ends_word(i: &str) -> bool {
+    let mut prev = i.chars().rev();
+
+    matches!(
+        (prev.next(), prev.next()),
+        (Some(a), Some(b)) if a.is_ascii_lowercase() && b.is_ascii_alphabetic() && a != 'v' && b != 'v'
+    )
+}
+
+/// Normalize operator shorthands (and, optionally, square and curly braces into parentheses)
+/// in a single pass over the input.
+pub(crate) fn normalize(i: &str, braces: bool) -> String {
     use consts::*;
-    
-    static BIC_REGEX: Lazy<(Regex, &'static str)> = Lazy::new(|| (Regex::new(r#"(?:<\->)"#).unwrap(), BIC) );
-    static IMP_REGEX: Lazy<(Regex, &'static str)> = Lazy::new(|| (Regex::new(r#"(?:\->)"#).unwrap(), IMP) );
-    static CON_REGEX: Lazy<(Regex, &'static str)> = Lazy::new(|| (Regex::new(r#"(?:\^|&)"#).unwrap(), CON) );
-    static DIS_REGEX: Lazy<(Regex, &'static str)> = Lazy::new(|| (Regex::new(r#"v"#).unwrap(), DIS) );
-    static NEG_REGEX: Lazy<(Regex, &'static str)> = Lazy::new(|| (Regex::new(r#"(?:~)"#).unwrap(), NEG) );
-    static BOT_REGEX: Lazy<(Regex, &'static str)> = Lazy::new(|| (Regex::new(r#"(?:XX|#)"#).unwrap(), BOT) );
-    static NEC_REGEX: Lazy<(Regex, &'static str)> = Lazy::new(|| (Regex::new(r#"\[\]"#).unwrap(), NEC) );
-    static POS_REGEX: Lazy<(Regex, &'static str)> = Lazy::new(|| (Regex::new(r#"<>"#).unwrap(), POS) );
-        
-    let pairs = [
-        BIC_REGEX.deref(),
-        IMP_REGEX.deref(),
-        CON_REGEX.deref(),
-        DIS_REGEX.deref(),
-        NEG_REGEX.deref(),
-        BOT_REGEX.deref(),
-        NEC_REGEX.deref(),
-        POS_REGEX.deref(),
-    ];
-
-    let mut out = i.to_owned();
-
-    for (regex, norm) in pairs {
-        out = regex.replace_all(&out, *norm).to_string();
+
+    // Alternatives are tried in order at each position, so longer shorthands
+    // must come before any of their prefixes (e.g. "<->" before "<>", "[]" before "[".)
+    static REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<\->|\->|\^|&|v|~|XX|#|\[\]|<>|◇|[\[\]\{\}]"#).unwrap() );
+
+    let mut out = String::with_capacity( i.len() );
+    let mut last = 0;
+
+    for m in REGEX.find_iter(i) {
+        out += &i[last..m.start()];
+        last = m.end();
+
+        let s = m.as_str();
+
+        out += match s {
+            "<->"      => BIC,
+            "->"       => IMP,
+            "^" | "&"  => CON,
+            // A "v" ending a word (e.g. the "Equiv" rule) is a letter, not a disjunction -
+            // but one after a lone lowercase sentence letter (as in "pvq") is still a disjunction.
+            // This is decided on what's been written out so far, so normalizing twice changes nothing.
+            "v" if ends_word(&out) => "v",
+            "v"        => DIS,
+            "~"        => NEG,
+            "XX" | "#" => BOT,
+            "[]"       => NEC,
+            // The white diamond is a common stand-in for the lozenge.
+            "<>" | "◇" => POS,
+            "[" | "{" if braces => "(",
+            "]" | "}" if braces => ")",
+            _          => s,
+        };
     }
 
+    out += &i[last..];
     out
-}
\ No newline at end of file
+}
@@ -1,13 +1,42 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-use super::normalize_ops;
+use super::normalize;
 use super::ParseError;
 use super::consts::*;
 
+/// Which letters can be used as atomic sentences.
+#[derive(Debug, Default, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum Alphabet {
+    /// A-Z.
+    #[default]
+    Uppercase,
+    /// a-z, except for `v` (which is always read as disjunction.)
+    Lowercase,
+    /// Both of the above.
+    Both,
+}
+
+impl Alphabet {
+    /// Whether `c` is an atomic sentence in this alphabet.
+    pub fn contains(self, c: char) -> bool {
+        let upper = c.is_ascii_uppercase();
+        let lower = c.is_ascii_lowercase() && c != 'v';
+
+        match self {
+            Self::Uppercase => upper,
+            Self::Lowercase => lower,
+            Self::Both      => upper || lower,
+        }
+    }
+}
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone)]
 pub enum Sentence {
-    /// An atomic predicate (A-Z, capitals only.)
+    /// An atomic predicate (A-Z by default - see [`Alphabet`].)
     Atomic(char),
     /// A "signal" operator (lone contradiction or necessity.)
     Signal(char),
@@ -29,11 +58,11 @@ pub enum Sentence {
 
 impl Sentence {
     pub fn parse(i: &str) -> Result<Self, ParseError> {
-        static SIGNAL_REGEX   : Lazy<Regex> = Lazy::new(|| Regex::new("^[⊥□]$").unwrap() );
-        static BOT_REGEX      : Lazy<Regex> = Lazy::new(|| Regex::new("⊥").unwrap() );
-        static ATOMIC_REGEX   : Lazy<Regex> = Lazy::new(|| Regex::new("^[A-Z]$").unwrap() );
-        static OP_REGEX       : Lazy<Regex> = Lazy::new(|| Regex::new("[¬∧∨↔→⊥□⋄]").unwrap() );
-        
+        Self::parse_with(i, Alphabet::Uppercase)
+    }
+
+    /// Parse a sentence whose atomic sentences are drawn from the given alphabet.
+    pub fn parse_with(i: &str, alphabet: Alphabet) -> Result<Self, ParseError> {
         // Take care of any loose whitespace before we proceed
         let i = i.trim();
 
@@ -43,39 +72,49 @@ impl Sentence {
         }
 
         // Normalize parenthesis and operator shorthands (i.e. <-> becomes ↔)
-        let i = normalize_braces( &normalize_ops(i) );
+        // once, up front - subsentences are then parsed as slices of the result.
+        let i = normalize(i, true);
+
+        Self::parse_normalized(&i, &i, alphabet)
+    }
+
+    /// Check that a sentence is well formed, without keeping its parse tree.
+    pub fn is_wff(i: &str) -> Result<(), ParseError> {
+        Self::is_wff_with(i, Alphabet::Uppercase)
+    }
+
+    /// Check that a sentence is well formed, with atomic sentences drawn from the given alphabet.
+    pub fn is_wff_with(i: &str, alphabet: Alphabet) -> Result<(), ParseError> {
+        Self::parse_with(i, alphabet).map(drop)
+    }
+
+    /// Parse an already-normalized sentence, recursing on subslices of the input.
+    /// 
+    /// `root` is the whole normalized sentence that `i` is a slice of, used for error positions.
+    fn parse_normalized(root: &str, i: &str, alphabet: Alphabet) -> Result<Self, ParseError> {
+        static SIGNAL_REGEX   : Lazy<Regex> = Lazy::new(|| Regex::new("^[⊥□]$").unwrap() );
+        static BOT_REGEX      : Lazy<Regex> = Lazy::new(|| Regex::new("⊥").unwrap() );
+        static ATOMIC_REGEX   : Lazy<Regex> = Lazy::new(|| Regex::new("^[A-Za-z]$").unwrap() );
+
+        let i = i.trim();
+
+        if i.is_empty() {
+            return Err(ParseError::EmptySentence)
+        }
 
         // Compute parenthesis depths
-        let d = compute_depths(&i)?;
+        let d = compute_depths(i)?;
 
         // Remove redundant outer parentheses
-        if d[0] == 1 {
-            let mut m = true;
-
-            for (n, _) in i
-                .chars()
-                .enumerate()
-                .skip(1)
-                .take(i.chars().count() - 2) 
-            {
-                m = m && d[n] > 0;
-            }
-            
-            if m {
-                let rest: String = i
-                    .chars()
-                    .skip(1)
-                    .take(i.chars().count() - 2)
-                    .collect();
-                
-                return Self::parse(&rest);
-            }
+        // (parentheses are always one byte, so the slice below is on char boundaries.)
+        if d[0] == 1 && d[1..d.len() - 1].iter().all(|d| *d > 0) {
+            return Self::parse_normalized( root, &i[1..i.len() - 1], alphabet );
         }
 
         // Check for any invalid characters that remain after normalization
-        invalid_chars(&i)?;
+        invalid_chars(i, alphabet)?;
 
-        if SIGNAL_REGEX.is_match(&i) {
+        if SIGNAL_REGEX.is_match(i) {
             let c = i.chars()
                 .nth(0)
                 .expect("Signal regular expression matched an empty string");
@@ -83,12 +122,13 @@ impl Sentence {
             return Ok( Self::Signal(c) )
         }
 
-        if BOT_REGEX.is_match(&i) {
+        if BOT_REGEX.is_match(i) {
             return Err(ParseError::BadContradiction)
         }
 
         // No operators means we should be dealing with an atomic.
-        if ATOMIC_REGEX.is_match(&i) {
+        // (Letters outside the alphabet were already rejected above.)
+        if ATOMIC_REGEX.is_match(i) {
             let c = i.chars()
                 .nth(0)
                 .expect("Atomic regular expression matched an empty string");
@@ -96,45 +136,27 @@ impl Sentence {
             return Ok( Self::Atomic(c) )
         }
 
-        let mut main_op_c = None;
-        let mut main_op_p = None;
-
-        // Locate the main operator.
-        for (n, c) in i.chars().enumerate() {
-            if OP_REGEX.is_match( &c.to_string() ) && d[n] == 0 {
-                match main_op_c {
-                    None => {
-                        main_op_c = Some(c);
-                        main_op_p = Some(n);
-                    },
-                    Some(m) => {
-                        if is_bin_op(m) && is_bin_op(c) {
-                            return Err(ParseError::Ambiguous)
-                        }
-                        else if is_una_op(m) && is_bin_op(c) {
-                            main_op_c = Some(c);
-                            main_op_p = Some(n);
-                        }
-                    }
-                }
-            }
-        }
+        // The main operator and its byte offset.
+        let main_op = find_main_op(root, i, &d)?;
 
-        let Some(main_op_c) = main_op_c.map(String::from) else {
+        let Some((main_op_c, main_op_p)) = main_op else {
             return Err(ParseError::MissingOp)
         };
 
-        let main_op_p = main_op_p.expect("Main operator position should be known");
+        let l = &i[..main_op_p];
+        let r = &i[main_op_p + main_op_c.len_utf8()..];
+
+        let mut buf = [0; 4];
+        let op = &*main_op_c.encode_utf8(&mut buf);
 
-        if matches!(main_op_c.as_str(), NEG | NEC | POS) {
+        if is_una_op(main_op_c) {
             if main_op_p != 0 {
                 return Err(ParseError::BadUnary)
             }
 
-            let rest = i.chars().skip(1).collect::<String>();
-            let rest = Box::new( Self::parse(&rest)? );
+            let rest = Box::new( Self::parse_normalized(root, r, alphabet)? );
 
-            return match main_op_c.as_str() {
+            return match op {
                 NEG => Ok( Self::Neg(rest) ),
                 NEC => Ok( Self::Nec(rest) ),
                 POS => Ok( Self::Pos(rest) ),
@@ -142,13 +164,10 @@ impl Sentence {
             }
         }
 
-        let l: String = i.chars().take(main_op_p).collect();
-        let r: String = i.chars().skip(main_op_p + 1).collect();
+        let l = Box::new( Self::parse_normalized(root, l, alphabet)? );
+        let r = Box::new( Self::parse_normalized(root, r, alphabet)? );
 
-        let l = Box::new( Self::parse(&l)? );
-        let r = Box::new( Self::parse(&r)? );
-
-        match main_op_c.as_str() {
+        match op {
             CON => Ok( Self::Con(l, r) ),
             DIS => Ok( Self::Dis(l, r) ),
             IMP => Ok( Self::Imp(l, r) ),
@@ -157,6 +176,36 @@ impl Sentence {
         }
     }
 
+    /// Locate the main operator of a well-formed sentence, along with its character offset
+    /// in the normalized input (see [`normalize_ops`](super::normalize_ops).)
+    /// 
+    /// Returns `None` if the sentence is malformed or has no operator (i.e. it is atomic or a signal.)
+    /// Letters from any [`Alphabet`] are accepted.
+    pub fn main_operator(i: &str) -> Option<(char, usize)> {
+        if let Self::Atomic(_) | Self::Signal(_) = Self::parse_with(i, Alphabet::Both).ok()? {
+            return None
+        }
+
+        let root = normalize(i, true);
+        let mut i = root.trim();
+
+        // Strip redundant outer parentheses, as in parsing.
+        let d = loop {
+            let d = compute_depths(i).ok()?;
+
+            if d[0] == 1 && d[1..d.len() - 1].iter().all(|d| *d > 0) {
+                i = i[1..i.len() - 1].trim();
+            } else {
+                break d
+            }
+        };
+
+        let (c, p) = find_main_op(&root, i, &d).ok()??;
+        let offset = i.as_ptr() as usize - root.as_ptr() as usize + p;
+
+        Some( (c, root[..offset].chars().count()) )
+    }
+
     pub fn is_nec_signal(&self) -> bool {
         let Sentence::Signal('□') = self else {
             return false
@@ -173,6 +222,22 @@ impl Sentence {
         true
     }
 
+    /// Returns this sentence with the operands of its main connective swapped,
+    /// if that connective is commutative (∧, ∨ or ↔.)
+    pub fn commuted(&self) -> Option<Self> {
+        match self {
+            Self::Con(l, r) => Some( Self::Con(r.clone(), l.clone()) ),
+            Self::Dis(l, r) => Some( Self::Dis(r.clone(), l.clone()) ),
+            Self::Bic(l, r) => Some( Self::Bic(r.clone(), l.clone()) ),
+            _ => None
+        }
+    }
+
+    /// Equality, treating the main connective as commutative where possible.
+    pub fn equiv_commutative(&self, other: &Self) -> bool {
+        self == other || self.commuted().as_ref() == Some(other)
+    }
+
     pub fn negated(&self) -> Self {
         Self::Neg( self.clone().box_up() )
     }
@@ -180,6 +245,225 @@ impl Sentence {
     pub fn box_up(self) -> Box<Self> {
         Box::new(self)
     }
+
+    /// Render this sentence as an indented outline of its parse tree, one node per line.
+    /// 
+    /// Each compound node is labeled with its main connective, followed by the subsentence it heads.
+    pub fn tree_string(&self) -> String {
+        fn walk(s: &Sentence, depth: usize, out: &mut Vec<String>) {
+            let indent = "  ".repeat(depth);
+
+            let (op, children): (&str, Vec<&Sentence>) = match s {
+                Sentence::Atomic(_) | Sentence::Signal(_) => {
+                    out.push( format!("{indent}{s}") );
+                    return
+                }
+                Sentence::Neg(c)    => (NEG, vec![c]),
+                Sentence::Nec(c)    => (NEC, vec![c]),
+                Sentence::Pos(c)    => (POS, vec![c]),
+                Sentence::Con(l, r) => (CON, vec![l, r]),
+                Sentence::Dis(l, r) => (DIS, vec![l, r]),
+                Sentence::Imp(l, r) => (IMP, vec![l, r]),
+                Sentence::Bic(l, r) => (BIC, vec![l, r]),
+            };
+
+            out.push( format!("{indent}{op}: {s}") );
+
+            for c in children {
+                walk(c, depth + 1, out);
+            }
+        }
+
+        let mut out = vec![];
+        walk(self, 0, &mut out);
+        out.join("\n")
+    }
+
+    /// Collect the atomic sentences that appear in this sentence.
+    pub fn atoms(&self) -> BTreeSet<char> {
+        let mut atoms = BTreeSet::new();
+
+        fn walk(s: &Sentence, atoms: &mut BTreeSet<char>) {
+            match s {
+                Sentence::Atomic(c) => { atoms.insert(*c); },
+                Sentence::Signal(_) => (),
+                Sentence::Neg(s) | Sentence::Nec(s) | Sentence::Pos(s) => walk(s, atoms),
+                Sentence::Con(l, r) | Sentence::Dis(l, r) | Sentence::Imp(l, r) | Sentence::Bic(l, r) => {
+                    walk(l, atoms);
+                    walk(r, atoms);
+                }
+            }
+        }
+
+        walk(self, &mut atoms);
+        atoms
+    }
+
+    /// The number of atoms, signals and operators in this sentence.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Atomic(_) | Self::Signal(_) => 1,
+            Self::Neg(s) | Self::Nec(s) | Self::Pos(s) => 1 + s.size(),
+            Self::Con(l, r) | Self::Dis(l, r) | Self::Imp(l, r) | Self::Bic(l, r) => 1 + l.size() + r.size(),
+        }
+    }
+
+    /// Collect every distinct subformula of this sentence, including itself and its atoms.
+    /// 
+    /// Subformulas are ordered by increasing [`size`](Self::size), and then left-to-right.
+    pub fn subformulas(&self) -> Vec<Sentence> {
+        let mut subs = vec![];
+
+        fn walk(s: &Sentence, subs: &mut Vec<Sentence>) {
+            match s {
+                Sentence::Atomic(_) | Sentence::Signal(_) => (),
+                Sentence::Neg(s) | Sentence::Nec(s) | Sentence::Pos(s) => walk(s, subs),
+                Sentence::Con(l, r) | Sentence::Dis(l, r) | Sentence::Imp(l, r) | Sentence::Bic(l, r) => {
+                    walk(l, subs);
+                    walk(r, subs);
+                }
+            }
+
+            if !subs.contains(s) {
+                subs.push( s.clone() );
+            }
+        }
+
+        walk(self, &mut subs);
+        subs.sort_by_key(Sentence::size);
+        subs
+    }
+
+    /// Evaluate this sentence under a truth-value assignment to its atomic sentences.
+    /// 
+    /// Returns `None` if the sentence isn't truth-functional (i.e. it contains a modal operator.)
+    pub fn evaluate(&self, v: &impl Fn(char) -> bool) -> Option<bool> {
+        match self {
+            Self::Atomic(c)   => Some( v(*c) ),
+            Self::Signal('⊥') => Some(false),
+            Self::Signal(_)   => None,
+            Self::Neg(s)      => Some( !s.evaluate(v)? ),
+            Self::Nec(_) | Self::Pos(_) => None,
+            Self::Con(l, r)   => Some( l.evaluate(v)? && r.evaluate(v)? ),
+            Self::Dis(l, r)   => Some( l.evaluate(v)? || r.evaluate(v)? ),
+            Self::Imp(l, r)   => Some( !l.evaluate(v)? || r.evaluate(v)? ),
+            Self::Bic(l, r)   => Some( l.evaluate(v)? == r.evaluate(v)? ),
+        }
+    }
+
+    /// Whether some truth-value assignment makes all of the given sentences true, by brute-force truth table.
+    /// 
+    /// Returns `None` if any sentence isn't truth-functional, or if there are more than
+    /// `MAX_TABLE_ATOMS` atomic sentences between them (making the table impractically large.)
+    pub fn jointly_satisfiable(sentences: &[Sentence]) -> Option<bool> {
+        Self::satisfying_assignment(sentences).map(|a| a.is_some() )
+    }
+
+    /// Find a truth-value assignment that makes all of the given sentences true, by brute-force truth table.
+    /// 
+    /// Returns `Some(None)` if there isn't one, and `None` under the same conditions as [`Sentence::jointly_satisfiable`].
+    pub fn satisfying_assignment(sentences: &[Sentence]) -> Option<Option<BTreeMap<char, bool>>> {
+        let atoms: Vec<_> = sentences
+            .iter()
+            .flat_map(Sentence::atoms)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        if atoms.len() > MAX_TABLE_ATOMS {
+            return None
+        }
+
+        let mut found = None;
+
+        for row in 0..1_u32 << atoms.len() {
+            let v = |c| {
+                let i = atoms.iter().position(|a| *a == c).expect("Every atom should be in the table");
+                row & (1 << i) != 0
+            };
+
+            let mut all = true;
+
+            for s in sentences {
+                all &= s.evaluate(&v)?;
+            }
+
+            // Keep going after a match, so that non-truth-functional sentences are always caught.
+            if all && found.is_none() {
+                found = Some(
+                    atoms
+                        .iter()
+                        .enumerate()
+                        .map(|(i, a)| (*a, row & (1 << i) != 0))
+                        .collect()
+                );
+            }
+        }
+
+        Some(found)
+    }
+
+    /// Find a truth-value assignment that makes all of the premises true but the conclusion false,
+    /// showing that the argument from one to the other is invalid.
+    /// 
+    /// Returns `Some(None)` if the argument is valid, and `None` under the same conditions as [`Sentence::jointly_satisfiable`].
+    pub fn counterexample(premises: &[Sentence], conclusion: &Sentence) -> Option<Option<BTreeMap<char, bool>>> {
+        let mut sentences = premises.to_vec();
+        sentences.push( conclusion.negated() );
+
+        Self::satisfying_assignment(&sentences)
+    }
+}
+
+/// The most atomic sentences [`Sentence::jointly_satisfiable`] will build a truth table for.
+pub const MAX_TABLE_ATOMS: usize = 16;
+
+/// A `proptest` strategy for well-formed sentences over the atoms A-D, optionally including modal operators.
+#[cfg(test)]
+pub(crate) fn arb_sentence(modal: bool) -> impl proptest::strategy::Strategy<Value = Sentence> {
+    use proptest::prelude::*;
+
+    let leaf = proptest::sample::select(vec!['A', 'B', 'C', 'D']).prop_map(Sentence::Atomic);
+
+    leaf.prop_recursive(4, 32, 2, move |inner| {
+        let mut ops = vec![
+            inner.clone().prop_map(|s| Sentence::Neg( s.box_up() ) ).boxed(),
+            (inner.clone(), inner.clone()).prop_map(|(l, r)| Sentence::Con( l.box_up(), r.box_up() ) ).boxed(),
+            (inner.clone(), inner.clone()).prop_map(|(l, r)| Sentence::Dis( l.box_up(), r.box_up() ) ).boxed(),
+            (inner.clone(), inner.clone()).prop_map(|(l, r)| Sentence::Imp( l.box_up(), r.box_up() ) ).boxed(),
+            (inner.clone(), inner.clone()).prop_map(|(l, r)| Sentence::Bic( l.box_up(), r.box_up() ) ).boxed(),
+        ];
+
+        if modal {
+            ops.push( inner.clone().prop_map(|s| Sentence::Nec( s.box_up() ) ).boxed() );
+            ops.push( inner.prop_map(|s| Sentence::Pos( s.box_up() ) ).boxed() );
+        }
+
+        proptest::strategy::Union::new(ops)
+    })
+}
+
+impl Display for Sentence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Binary subsentences are parenthesized; everything else binds tightly enough not to need it.
+        let sub = |s: &Sentence| -> String {
+            match s {
+                Self::Con(..) | Self::Dis(..) | Self::Imp(..) | Self::Bic(..) => format!("({s})"),
+                _ => s.to_string()
+            }
+        };
+
+        match self {
+            Self::Atomic(c) | Self::Signal(c) => write!(f, "{c}"),
+            Self::Neg(s)    => write!(f, "{NEG}{}", sub(s)),
+            Self::Nec(s)    => write!(f, "{NEC}{}", sub(s)),
+            Self::Pos(s)    => write!(f, "{POS}{}", sub(s)),
+            Self::Con(l, r) => write!(f, "{} {CON} {}", sub(l), sub(r)),
+            Self::Dis(l, r) => write!(f, "{} {DIS} {}", sub(l), sub(r)),
+            Self::Imp(l, r) => write!(f, "{} {IMP} {}", sub(l), sub(r)),
+            Self::Bic(l, r) => write!(f, "{} {BIC} {}", sub(l), sub(r)),
+        }
+    }
 }
 
 impl PartialEq<&Box<Sentence>> for Sentence {
@@ -208,32 +492,12 @@ impl PartialEq<&Sentence> for &Box<Sentence> {
     }
 }
 
-/// Normalize braces.
-fn normalize_braces(i: &str) -> String {
-    use std::ops::Deref;
-    
-    static BRO_REGEX: Lazy<(Regex, &'static str)> = Lazy::new(|| (Regex::new(r#"(?:\[|\{)"#).unwrap(), "(") );
-    static BRC_REGEX: Lazy<(Regex, &'static str)> = Lazy::new(|| (Regex::new(r#"(?:\]|\})"#).unwrap(), ")") );
-    
-    let pairs = [
-        BRO_REGEX.deref(),
-        BRC_REGEX.deref()
-    ];
-
-    let mut out = i.to_owned();
-
-    for (regex, norm) in pairs {
-        out = regex.replace_all(&out, *norm).to_string();
-    }
-
-    out
-}
-
-fn invalid_chars(i: &str) -> Result<(), ParseError> {
-    static REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"[^A-Z¬∨∧↔→⊥□⋄\s\)\(\]\[\}\{]"#).unwrap() );
+fn invalid_chars(i: &str, alphabet: Alphabet) -> Result<(), ParseError> {
+    static REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"[^¬∨∧↔→⊥□⋄\s\)\(\]\[\}\{]"#).unwrap() );
 
     let captures: Vec<_> = REGEX.find_iter(i)
         .map(|m| m.as_str() )
+        .filter(|s| !s.chars().all(|c| alphabet.contains(c)) )
         .map(|s| s.to_owned() )
         .collect();
 
@@ -244,6 +508,35 @@ fn invalid_chars(i: &str) -> Result<(), ParseError> {
     Ok(())
 }
 
+/// Locate the main operator of `i` (a slice of `root`) and its byte offset, given its parenthesis depths.
+fn find_main_op(root: &str, i: &str, d: &[u16]) -> Result<Option<(char, usize)>, ParseError> {
+    let mut main_op: Option<(char, usize)> = None;
+
+    for ((p, c), d) in i.char_indices().zip( d.iter() ) {
+        if (is_una_op(c) || is_bin_op(c)) && *d == 0 {
+            match main_op {
+                None => main_op = Some( (c, p) ),
+                Some((m, _)) => {
+                    if is_bin_op(m) && is_bin_op(c) {
+                        let offset = i.as_ptr() as usize - root.as_ptr() as usize + p;
+
+                        return Err(ParseError::Ambiguous {
+                            first: m,
+                            second: c,
+                            at: root[..offset].chars().count()
+                        })
+                    }
+                    else if is_una_op(m) && is_bin_op(c) {
+                        main_op = Some( (c, p) );
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(main_op)
+}
+
 fn compute_depths(i: &str) -> Result<Box<[u16]>, ParseError> {
     let mut c_depth = 0_u16;
     let mut v_depth = vec![];
@@ -301,11 +594,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn alphabets() {
+        let lower = |i| Sentence::parse_with(i, Alphabet::Lowercase);
+
+        assert_eq!(
+            lower("p -> q").unwrap(),
+            Sentence::Imp(
+                Sentence::Atomic('p').box_up(),
+                Sentence::Atomic('q').box_up()
+            )
+        );
+
+        assert_eq!(
+            lower("pvq").unwrap(),
+            Sentence::Dis(
+                Sentence::Atomic('p').box_up(),
+                Sentence::Atomic('q').box_up()
+            )
+        );
+
+        assert_eq!(
+            lower("p ^ Q").unwrap_err(),
+            ParseError::InvalidCharacter(vec!["Q".to_owned()])
+        );
+
+        assert_eq!(
+            Sentence::parse("p -> q").unwrap_err(),
+            ParseError::InvalidCharacter(vec!["p".to_owned(), "q".to_owned()])
+        );
+
+        assert_eq!(
+            Sentence::parse_with("p <-> Q", Alphabet::Both).unwrap(),
+            Sentence::Bic(
+                Sentence::Atomic('p').box_up(),
+                Sentence::Atomic('Q').box_up()
+            )
+        );
+
+        assert_eq!( Sentence::main_operator("p -> q"), Some(('→', 2)) );
+    }
+
     #[test]
     fn ambiguity() {
         assert_eq!(
             Sentence::parse("A ^^ B").unwrap_err(),
-            ParseError::Ambiguous
+            ParseError::Ambiguous { first: '∧', second: '∧', at: 3 }
+        );
+
+        assert_eq!(
+            Sentence::parse("~(A ^ B v C)").unwrap_err(),
+            ParseError::Ambiguous { first: '∧', second: '∨', at: 8 }
         );
     }
 
@@ -336,6 +675,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn is_wff() {
+        assert_eq!( Sentence::is_wff("(A -> B) ^ ~[]C"), Ok(()) );
+        assert_eq!( Sentence::is_wff("#"), Ok(()) );
+
+        assert_eq!( Sentence::is_wff("  "), Err(ParseError::EmptySentence) );
+        assert_eq!( Sentence::is_wff("(A ^ B"), Err(ParseError::UnbalancedParentheses) );
+        assert_eq!( Sentence::is_wff("A!"), Err(ParseError::InvalidCharacter(vec!["!".to_owned()])) );
+        assert_eq!( Sentence::is_wff("A ^^ B"), Err(ParseError::Ambiguous { first: '∧', second: '∧', at: 3 }) );
+        assert_eq!( Sentence::is_wff("A B"), Err(ParseError::MissingOp) );
+        assert_eq!( Sentence::is_wff("A¬B"), Err(ParseError::BadUnary) );
+        assert_eq!( Sentence::is_wff("A ^ #"), Err(ParseError::BadContradiction) );
+
+        assert_eq!( Sentence::is_wff("p -> q"), Err(ParseError::InvalidCharacter(vec!["p".to_owned(), "q".to_owned()])) );
+        assert_eq!( Sentence::is_wff_with("p -> q", Alphabet::Lowercase), Ok(()) );
+    }
+
     #[test]
     fn atomic() {
         let s = Sentence::parse("A").unwrap();
@@ -346,6 +702,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn bot_round_trip() {
+        // Every spelling of contradiction displays as ⊥, which reads back as the same sentence.
+        for alphabet in [Alphabet::Uppercase, Alphabet::Lowercase, Alphabet::Both] {
+            for bot in ["#", "XX", "⊥", " ⊥ "] {
+                let s = Sentence::parse_with(bot, alphabet).unwrap();
+
+                assert_eq!( s.to_string(), "⊥" );
+                assert_eq!( Sentence::parse_with(&s.to_string(), alphabet), Ok(s) );
+            }
+        }
+
+        // The same goes for whole lines, citations included.
+        let p = crate::parse::Proof::parse([
+            (0, "A", "PR"),
+            (0, "~A", "PR"),
+            (0, "XX", "~E 1, 2"),
+            (0, "#", "XXI 2, 1"),
+            (0, "B", "X 4"),
+        ]).unwrap();
+
+        let shown: Vec<_> = p.lines
+            .iter()
+            .map(|l| (l.d, l.s.to_string(), l.c.to_string()) )
+            .collect();
+
+        assert_eq!( shown[2], (0, String::from("⊥"), String::from("¬E 1, 2")) );
+        assert_eq!( shown[3], (0, String::from("⊥"), String::from("⊥I 2, 1")) );
+
+        let shown: Vec<_> = shown
+            .iter()
+            .map(|(d, s, c)| (*d, s.as_str(), c.as_str()) )
+            .collect();
+
+        assert_eq!( crate::parse::Proof::parse(shown), Ok(p) );
+    }
+
     #[test]
     fn signal() {
         let bot = Sentence::parse("#").unwrap();
@@ -482,4 +875,146 @@ mod tests {
             )
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn display() {
+        for i in ["A", "⊥", "¬A", "□⋄A", "¬(A ∧ B)", "(A ∨ B) → ¬C", "A ↔ (B → (C ∧ D))"] {
+            let s = Sentence::parse(i).unwrap();
+
+            assert_eq!(s.to_string(), i);
+            assert_eq!(Sentence::parse( &s.to_string() ).unwrap(), s);
+        }
+    }
+
+    #[test]
+    fn equiv_commutative() {
+        let s = |i| Sentence::parse(i).unwrap();
+
+        assert!( s("A ^ B").equiv_commutative(&s("B ^ A")) );
+        assert!( s("A v (B ^ C)").equiv_commutative(&s("(B ^ C) v A")) );
+        assert!( s("A <-> B").equiv_commutative(&s("B <-> A")) );
+        assert!( !s("A -> B").equiv_commutative(&s("B -> A")) );
+        // Only the main connective commutes.
+        assert!( !s("A v (B ^ C)").equiv_commutative(&s("(C ^ B) v A")) );
+    }
+
+    #[test]
+    fn evaluate() {
+        let s = Sentence::parse("(A -> B) <-> (~A v B)").unwrap();
+
+        for (a, b) in [(false, false), (false, true), (true, false), (true, true)] {
+            assert_eq!( s.evaluate(&|c| if c == 'A' { a } else { b }), Some(true) );
+        }
+
+        let s = Sentence::parse("A ^ ~B").unwrap();
+
+        assert_eq!( s.evaluate(&|c| c == 'A'), Some(true) );
+        assert_eq!( s.evaluate(&|_| true), Some(false) );
+        assert_eq!( s.atoms().into_iter().collect::<Vec<_>>(), vec!['A', 'B'] );
+
+        assert_eq!( Sentence::parse("#").unwrap().evaluate(&|_| true), Some(false) );
+        assert_eq!( Sentence::parse("A -> []A").unwrap().evaluate(&|_| true), None );
+    }
+
+    #[test]
+    fn jointly_satisfiable() {
+        let s = |i: &[&str]| -> Vec<Sentence> { i.iter().map(|i| Sentence::parse(i).unwrap() ).collect() };
+
+        assert_eq!( Sentence::jointly_satisfiable(&s(&["A", "~A"])), Some(false) );
+        assert_eq!( Sentence::jointly_satisfiable(&s(&["A -> B", "A", "~B"])), Some(false) );
+        assert_eq!( Sentence::jointly_satisfiable(&s(&["A v B", "~A"])), Some(true) );
+        assert_eq!( Sentence::jointly_satisfiable(&s(&["#"])), Some(false) );
+        assert_eq!( Sentence::jointly_satisfiable(&[]), Some(true) );
+        assert_eq!( Sentence::jointly_satisfiable(&s(&["[]A", "~A"])), None );
+    }
+
+    #[test]
+    fn subformulas() {
+        let subs = |i| -> Vec<String> {
+            Sentence::parse(i).unwrap().subformulas().iter().map(Sentence::to_string).collect()
+        };
+
+        assert_eq!( subs("A"), vec!["A"] );
+        assert_eq!(
+            subs("(A -> B) ^ ~(A -> B)"),
+            vec!["A", "B", "A → B", "¬(A → B)", "(A → B) ∧ ¬(A → B)"]
+        );
+        assert_eq!(
+            subs("[]C v (B <-> C)"),
+            vec!["C", "B", "□C", "B ↔ C", "□C ∨ (B ↔ C)"]
+        );
+    }
+
+    #[test]
+    fn counterexample() {
+        let s = |i: &str| Sentence::parse(i).unwrap();
+
+        // Affirming the consequent.
+        assert_eq!(
+            Sentence::counterexample(&[s("A -> B"), s("B")], &s("A")),
+            Some( Some( BTreeMap::from([('A', false), ('B', true)]) ) )
+        );
+
+        assert_eq!( Sentence::counterexample(&[s("A -> B"), s("A")], &s("B")), Some(None) );
+        assert_eq!( Sentence::counterexample(&[], &s("A v ~A")), Some(None) );
+        assert_eq!( Sentence::counterexample(&[s("[]A")], &s("A")), None );
+
+        assert_eq!(
+            Sentence::satisfying_assignment(&[s("A ^ ~B")]),
+            Some( Some( BTreeMap::from([('A', true), ('B', false)]) ) )
+        );
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn display_round_trip(s in arb_sentence(true)) {
+            proptest::prop_assert_eq!( Sentence::parse( &s.to_string() ), Ok(s) );
+        }
+
+        #[test]
+        fn subformulas_distinct(s in arb_sentence(true)) {
+            let subs = s.subformulas();
+
+            proptest::prop_assert_eq!( subs.last(), Some(&s) );
+            proptest::prop_assert!( subs.windows(2).all(|w| w[0].size() <= w[1].size() ) );
+            proptest::prop_assert_eq!( subs.iter().collect::<std::collections::HashSet<_>>().len(), subs.len() );
+        }
+    }
+
+    #[test]
+    fn normalization() {
+        assert_eq!(
+            normalize("[(A <-> B) -> ~C] ^ {D v XX} & <>E # []F", true),
+            "((A ↔ B) → ¬C) ∧ (D ∨ ⊥) ∧ ⋄E ⊥ □F"
+        );
+
+        assert_eq!(normalize("[]A v [B] ; Equiv", false), "□A ∨ [B] ; Equiv");
+        assert_eq!(normalize("◇A", false), "⋄A");
+
+        // Normalizing twice changes nothing, as the UI re-normalizes on every keystroke.
+        for i in ["XXqv", "#pv", "Equiv 1", "pvq", "[(A <-> B) -> ~C] ^ {D v XX}"] {
+            let once = normalize(i, true);
+            assert_eq!( normalize(&once, true), once, "{i}" );
+        }
+
+        assert_eq!(normalize("XXqv", false), "⊥q∨");
+    }
+
+    #[test]
+    fn main_operator() {
+        assert_eq!(Sentence::main_operator("A"), None);
+        assert_eq!(Sentence::main_operator("#"), None);
+        assert_eq!(Sentence::main_operator("A ^"), None);
+        assert_eq!(Sentence::main_operator("~(A ^ B)"), Some( ('¬', 0) ));
+        assert_eq!(Sentence::main_operator(" ((A ^ B) -> C)"), Some( ('→', 10) ));
+        assert_eq!(Sentence::main_operator("[A v B] <-> []C"), Some( ('↔', 8) ));
+    }
+
+    #[test]
+    fn tree_string() {
+        assert_eq!(
+            Sentence::parse("(A ^ ~B) -> C").unwrap().tree_string(),
+            "→: (A ∧ ¬B) → C\n  ∧: A ∧ ¬B\n    A\n    ¬: ¬B\n      B\n  C"
+        );
+    }
+}
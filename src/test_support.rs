@@ -0,0 +1,50 @@
+//! Helpers for building and checking proofs in tests and examples.
+
+use crate::check::{Checker, CheckErrors, Ruleset};
+use crate::parse::Proof;
+
+/// Create a checker with the given rulesets enabled, on top of the premise and assumption rules.
+pub fn build_checker(rulesets: &[Ruleset]) -> Checker {
+    let mut checker = Checker::new();
+
+    for ruleset in rulesets {
+        checker.add_ruleset(ruleset);
+    }
+
+    checker
+}
+
+/// Parse `(depth, sentence, citation)` lines into a proof, and check it with the given rulesets enabled.
+/// 
+/// Returns the proof if it checks, or the errors if it doesn't. Panics if the proof fails to parse.
+/// 
+/// ```
+/// use deduct::check::rulesets::TFL_BASIC;
+/// use deduct::check::CheckError;
+/// use deduct::test_support::build_proof;
+/// 
+/// assert!(build_proof(&[TFL_BASIC], &[
+///     (0, "A", "PR"),
+///     (0, "B", "PR"),
+///     (0, "A ^ B", "^I 1 2"),
+///     (0, "B ^ A", "^I 1 2"),
+/// ]).is_ok());
+/// 
+/// assert_eq!(
+///     build_proof(&[TFL_BASIC], &[
+///         (0, "A", "PR"),
+///         (0, "A ^ B", "^I 1 1"),
+///     ]).unwrap_err(),
+///     vec![(2, CheckError::BadUsage)]
+/// );
+/// ```
+pub fn build_proof(rulesets: &[Ruleset], lines: &[(u16, &str, &str)]) -> Result<Proof, CheckErrors> {
+    let p = match Proof::parse(lines) {
+        Ok(p) => p,
+        Err(e) => panic!("Failed to parse proof: {e:?}")
+    };
+
+    build_checker(rulesets).check_proof(&p)?;
+
+    Ok(p)
+}
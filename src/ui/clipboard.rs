@@ -0,0 +1,118 @@
+//! Copying exports to the system clipboard, with a short confirmation toast.
+
+use egui::*;
+
+/// How long a toast stays on screen, in seconds.
+const TOAST_DURATION: f64 = 2.0;
+
+/// Copy `text` to the clipboard, and toast the outcome.
+/// 
+/// On the web, the browser writes to the clipboard in the background and may refuse to (e.g. without permission);
+/// if it does, the toast is replaced with an error once the refusal comes in.
+pub fn copy(ctx: &Context, text: String) {
+    match imp::copy(text) {
+        Ok(()) => toast(ctx, "Copied."),
+        Err(e) => toast(ctx, format!("Couldn't copy: {e}.")),
+    }
+}
+
+/// Briefly show a message at the bottom of the window.
+pub fn toast(ctx: &Context, message: impl Into<String>) {
+    let until = ctx.input(|i| i.time) + TOAST_DURATION;
+
+    ctx.data_mut(|d| d.insert_temp(toast_id(), (message.into(), until)) );
+}
+
+/// Draw the current toast, if any. Call once per frame.
+pub fn show_toast(ctx: &Context) {
+    if let Some(e) = imp::take_error() {
+        toast(ctx, format!("Couldn't copy: {e}."));
+    }
+
+    let Some((message, until)) = ctx.data(|d| d.get_temp::<(String, f64)>(toast_id()) ) else {
+        return
+    };
+
+    let now = ctx.input(|i| i.time);
+
+    if now >= until {
+        ctx.data_mut(|d| d.remove::<(String, f64)>(toast_id()) );
+        return
+    }
+
+    Area::new(toast_id())
+        .order(Order::Tooltip)
+        .anchor(Align2::CENTER_BOTTOM, vec2(0.0, -20.0))
+        .interactable(false)
+        .show(ctx, |ui| {
+            Frame::popup(ui.style()).show(ui, |ui| ui.label(message) );
+        });
+
+    ctx.request_repaint_after( std::time::Duration::from_secs_f64(until - now) );
+}
+
+fn toast_id() -> Id {
+    Id::new("clipboard_toast")
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod imp {
+    use std::cell::RefCell;
+
+    thread_local! {
+        // Kept around for the life of the app - on Linux, copied text is
+        // only served for as long as the clipboard that set it exists.
+        static CLIPBOARD: RefCell<Option<arboard::Clipboard>> = const { RefCell::new(None) };
+    }
+
+    pub fn copy(text: String) -> Result<(), String> {
+        CLIPBOARD.with_borrow_mut(|clipboard| {
+            if clipboard.is_none() {
+                *clipboard = Some( arboard::Clipboard::new().map_err(|e| e.to_string())? );
+            }
+
+            clipboard
+                .as_mut()
+                .expect("Clipboard should have just been opened")
+                .set_text(text)
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    pub fn take_error() -> Option<String> {
+        None
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod imp {
+    use std::cell::RefCell;
+
+    use eframe::web_sys;
+    use wasm_bindgen_futures::JsFuture;
+
+    thread_local! {
+        // Set when the browser refuses a write, which only happens after `copy` has returned.
+        static ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+    }
+
+    pub fn copy(text: String) -> Result<(), String> {
+        let clipboard = web_sys::window()
+            .and_then(|w| w.navigator().clipboard() )
+            .ok_or_else(|| String::from("this browser doesn't allow access to the clipboard") )?;
+
+        let write = JsFuture::from( clipboard.write_text(&text) );
+
+        wasm_bindgen_futures::spawn_local(async move {
+            if write.await.is_err() {
+                ERROR.set( Some(String::from("the browser denied access to the clipboard")) );
+            }
+        });
+
+        Ok(())
+    }
+
+    pub fn take_error() -> Option<String> {
+        ERROR.take()
+    }
+}
@@ -1,8 +1,13 @@
 use egui::*;
 use serde::{Deserialize, Serialize};
 
+use crate::check::rulesets;
+use crate::parse::Proof;
+
+mod clipboard;
 mod popups;
 mod proof;
+mod share;
 
 const MODIFIER: Modifiers = Modifiers::ALT;
 
@@ -54,8 +59,35 @@ const NEW_SO: KeyboardShortcut = KeyboardShortcut::new(
     Key::L
 );
 
+#[cfg(not(target_arch = "wasm32"))]
+const CHECK: KeyboardShortcut = KeyboardShortcut::new(
+    MODIFIER,
+    Key::C
+);
+
+#[cfg(not(target_arch = "wasm32"))]
+const FIRST_ERROR: KeyboardShortcut = KeyboardShortcut::new(
+    MODIFIER,
+    Key::E
+);
+
+#[cfg(target_arch = "wasm32")]
+const CHECK: KeyboardShortcut = KeyboardShortcut::new(
+    MODIFIER,
+    Key::U
+);
+
+#[cfg(target_arch = "wasm32")]
+const FIRST_ERROR: KeyboardShortcut = KeyboardShortcut::new(
+    MODIFIER,
+    Key::J
+);
+
 const UI_ZOOM_FACTORS: [f32; 5] = [1.0, 1.25, 1.50, 1.75, 2.0];
 
+/// The fraction of the window's width taken up by the rule reference, when it's shown.
+const REFERENCE_FRACTION: f32 = 0.25;
+
 /// Top-level application state.
 #[derive(Serialize, Deserialize, Default)]
 #[serde(default)]
@@ -80,26 +112,29 @@ impl Deduct {
 
         fonts_init(cc);
 
+        let mut app: Self = Default::default();
+
         if let Some(storage) = cc.storage {
-            let loaded: Self = eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            app = eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
 
-            cc.egui_ctx.set_zoom_factor(UI_ZOOM_FACTORS[loaded.prefs.ui_scale]);
+            cc.egui_ctx.set_zoom_factor(UI_ZOOM_FACTORS[app.prefs.ui_scale]);
             
-            match loaded.prefs.dark_mode {
+            match app.prefs.dark_mode {
                 false => cc.egui_ctx.set_visuals(Visuals::light()), 
                 true => cc.egui_ctx.set_visuals(Visuals::dark())
             }
-
-            return loaded;
         }
 
-        Default::default()
+        // Open the proof from a shared link, if there is one. Broken links are ignored.
+        app.proof = share::from_page().and_then(proof::ProofUi::from_shared);
+
+        app
     }
 
     /// Try and use the input from the new proof popup
     /// to start a new proof.
     pub fn try_new_proof(&mut self) {
-        if let Some(ui) = self.new.try_create() {
+        if let Some(ui) = self.new.try_create(&self.prefs) {
             self.proof = Some(ui);
             self.vis.new_proof = false;
         }
@@ -109,8 +144,10 @@ impl Deduct {
     /// Handle keyboard shortcuts.
     fn handle_shortcuts(&mut self, ctx: &Context) {
         let mut op = None;
+        let mut jump = false;
 
-        let Some(proof) = &mut self.proof else {
+        // Replays are read-only.
+        let Some(proof) = self.proof.as_mut().filter(|p| p.replay.is_none() ) else {
             return
         };
 
@@ -140,8 +177,22 @@ impl Deduct {
                     if d == 0 { 1 } else { d } 
                 ));
             }
+
+            // Force a full re-parse and re-check, even if the proof is too large for live checking.
+            if i.consume_shortcut(&CHECK) {
+                proof.updated = true;
+                proof.check_now = true;
+            }
+
+            if i.consume_shortcut(&FIRST_ERROR) {
+                jump = true;
+            }
         });
 
+        if jump && !proof.jump_to_first_error() {
+            clipboard::toast(ctx, "No errors to jump to.");
+        }
+
         if let Some((idx, premise, depth)) = op {
             ctx.memory_mut(|m| m.stop_text_input() );
             proof.insert_line(idx, premise, depth);
@@ -174,6 +225,12 @@ impl eframe::App for Deduct {
                     };
 
                     if ui.button("Edit Argument").clicked && self.proof.is_some() {
+                        // Start from the rulesets the proof is actually using.
+                        if let Some(proof) = &self.proof {
+                            self.new.rules  = proof.rules;
+                            self.new.custom = proof.custom;
+                        }
+
                         self.vis.new_proof = true;
                         ui.close_menu();
                     }
@@ -182,6 +239,50 @@ impl eframe::App for Deduct {
                         self.try_new_proof();
                         ui.close_menu();
                     };
+
+                    if let Some(proof) = &mut self.proof {
+                        ui.menu_button("Export", |ui| {
+                            if ui.button("Copy as Carnap").clicked() {
+                                proof.copy_proof(ui.ctx(), Proof::to_carnap);
+                                ui.close_menu();
+                            }
+
+                            if ui
+                                .button("Copy as Compact Text")
+                                .on_hover_text("A single line of text, for sharing in chat")
+                                .clicked()
+                            {
+                                proof.copy_proof(ui.ctx(), Proof::to_compact);
+                                ui.close_menu();
+                            }
+
+                            if ui.button("Copy as Graphviz DOT").clicked() {
+                                proof.copy_proof(ui.ctx(), |p| p.dependency_graph().to_dot() );
+                                ui.close_menu();
+                            }
+
+                            if ui
+                                .button("Copy Share Link")
+                                .on_hover_text("A link that opens this proof in the web version of Deduct")
+                                .clicked()
+                            {
+                                proof.share_link(ui.ctx());
+                                ui.close_menu();
+                            }
+                        });
+
+                        let replaying = proof.replay.is_some();
+                        let label = if replaying { "Stop Replay" } else { "Replay" };
+
+                        if ui
+                            .button(label)
+                            .on_hover_text("Step through the proof one line at a time, with each justification explained")
+                            .clicked()
+                        {
+                            proof.set_replay(!replaying);
+                            ui.close_menu();
+                        }
+                    }
                 });
 
                 ui.menu_button("Help", |ui| {
@@ -210,6 +311,16 @@ impl eframe::App for Deduct {
                     self.vis.settings = true;
                 }
 
+                let label = if self.prefs.hide_rules { "Show Reference" } else { "Hide Reference" };
+
+                if ui
+                    .button(label)
+                    .on_hover_text("Toggle the sidebar of rules and shorthands")
+                    .clicked()
+                {
+                    self.prefs.hide_rules = !self.prefs.hide_rules;
+                }
+
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::RIGHT), |ui| {
                     egui::warn_if_debug_build(ui);
                 });
@@ -220,9 +331,9 @@ impl eframe::App for Deduct {
         // Render quick reference side bar.
         egui::SidePanel::right("proof_rules")
             .resizable(false)
-            .min_width(w * 0.25)
-            .max_width(w * 0.25)
-            .show(ctx, |ui| {
+            .min_width(w * REFERENCE_FRACTION)
+            .max_width(w * REFERENCE_FRACTION)
+            .show_animated(ctx, !self.prefs.hide_rules, |ui| {
                 containers::ScrollArea::vertical().show(ui, |ui| {                   
                     let tint = if self.prefs.dark_mode { Color32::WHITE } else { Color32::BLACK };
 
@@ -273,7 +384,7 @@ impl eframe::App for Deduct {
                             ui.end_row();
 
                             ui.label("Contradiction");
-                            ui.label("XX or #");
+                            ui.label("XX or #").on_hover_text("Written as ⊥ - which can also be typed directly.");
                             ui.end_row();
 
                             ui.label("Necessity");
@@ -296,21 +407,36 @@ impl eframe::App for Deduct {
                         rule!(ui, "static/rules/TFLD.png");
                     });
 
-                    ui.collapsing("System K", |ui| {
-                        rule!(ui, "static/rules/K.png");
-                    });
+                    let modal = |ui: &mut Ui| {
+                        ui.collapsing("System K", |ui| {
+                            rule!(ui, "static/rules/K.png");
+                        });
 
-                    ui.collapsing("System T", |ui| {
-                        rule!(ui, "static/rules/RT.png");
-                    });
+                        ui.collapsing("System T", |ui| {
+                            rule!(ui, "static/rules/RT.png");
+                        });
 
-                    ui.collapsing("System S4", |ui| {
-                        rule!(ui, "static/rules/R4.png");
-                    });
+                        ui.collapsing("System S4", |ui| {
+                            rule!(ui, "static/rules/R4.png");
+                        });
 
-                    ui.collapsing("System S5", |ui| {
-                        rule!(ui, "static/rules/R5.png");
+                        ui.collapsing("System S5", |ui| {
+                            rule!(ui, "static/rules/R5.png");
+                        });
+                    };
+
+                    ui.collapsing("Classical Axioms", |ui| {
+                        for rule in rulesets::catalog().iter().filter(|r| r.ruleset == "Classical Axioms") {
+                            ui.label( RichText::new(rule.id).strong() );
+                            ui.label(format!("{}: {}", rule.name, rule.schema));
+                        }
                     });
+
+                    // Beginners get the modal systems tucked away under a single heading.
+                    match self.prefs.basic {
+                        true  => { ui.collapsing("Modal Logic", modal); },
+                        false => modal(ui)
+                    }
                 });
         });
 
@@ -327,12 +453,21 @@ impl eframe::App for Deduct {
                     return;
                 };
 
+                proof.show_trees = self.prefs.parse_trees;
+                proof.main_ops   = self.prefs.main_ops;
+                proof.wrap       = self.prefs.wrap;
+                proof.wide       = self.prefs.hide_rules;
+                proof.window     = w;
                 proof.ui(ui);
             });
 
         new_window("Preferences", &mut self.vis.settings)
             .show(ctx, |ui| self.prefs.ui(ui) );
 
+        self.new.basic    = self.prefs.basic;
+        self.new.alphabet = self.prefs.alphabet();
+        self.new.editing  = self.proof.is_some();
+
         new_window("New Proof", &mut self.vis.new_proof)
             .min_width(w * 0.50)
             .max_width(w * 0.50)
@@ -342,11 +477,22 @@ impl eframe::App for Deduct {
             self.try_new_proof();
         }
 
+        if self.new.apply {
+            if let Some(proof) = &mut self.proof {
+                proof.change_rules(self.new.rules, self.new.custom, self.new.checker(&self.prefs));
+            }
+
+            self.vis.new_proof = false;
+            self.new.apply = false;
+        }
+
         new_window("About", &mut self.vis.about)
             .show(ctx, about);
 
         new_window("Keyboard Shortcuts", &mut self.vis.shortcuts)
             .show(ctx, shortcuts);
+
+        clipboard::show_toast(ctx);
     }
 }
 
@@ -401,7 +547,7 @@ fn about(ui: &mut Ui) {
 
 /// Render the shortcut info window.
 fn shortcuts(ui: &mut Ui) {
-    ui.label("All shortcuts act on the currently selected line or (if no line is selected) the last line.");
+    ui.label("All insertion shortcuts act on the currently selected line or (if no line is selected) the last line.");
     ui.separator();
 
     ui.horizontal(|ui| {
@@ -451,6 +597,32 @@ fn shortcuts(ui: &mut Ui) {
             );
         });
     });
+
+    ui.separator();
+
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new("Check the proof now").strong()
+        );
+        
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            ui.label(
+                ui.ctx().format_shortcut(&CHECK)
+            );
+        });
+    });
+
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new("Jump to the first error").strong()
+        );
+        
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            ui.label(
+                ui.ctx().format_shortcut(&FIRST_ERROR)
+            );
+        });
+    });
 }
 
 /// Load LaTeX `Latin Modern Math` font into memory under the name `math`.
@@ -470,19 +642,12 @@ fn fonts_init(cc: &eframe::CreationContext<'_>) {
     cc.egui_ctx.set_fonts(fonts);
 }
 
-/// Return the current width and height of the window.
-fn window_size(ui: &Ui) -> (f32, f32) {
-    let w = ui.ctx().input(|i| {
-        let r = i.screen_rect().x_range();
-        r.max
-    });
-
-    let h = ui.ctx().input(|i| {
+/// Return the current height of the window.
+fn window_height(ui: &Ui) -> f32 {
+    ui.ctx().input(|i| {
         let r = i.screen_rect().y_range();
         r.max
-    });
-
-    (w, h)
+    })
 }
 
 /// Generate a dummy [`Response`] that does not influence the UI.
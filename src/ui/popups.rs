@@ -2,12 +2,18 @@ use egui::*;
 use serde::{Serialize, Deserialize};
 
 use crate::check::*;
-use crate::parse::Sentence;
+use crate::parse::{Alphabet, Sentence};
 use crate::parse::normalize_ops;
 
 use super::UI_ZOOM_FACTORS;
 use super::proof::*;
 
+/// Each selectable [`Alphabet`], with its display name.
+const ALPHABETS: [(Alphabet, &str); 3] = [
+    (Alphabet::Uppercase, "Uppercase (A-Z)"),
+    (Alphabet::Lowercase, "Lowercase (a-z)"),
+    (Alphabet::Both, "Both"),
+];
 
 #[derive(Debug, Default)]
 pub struct Visibility {
@@ -20,28 +26,48 @@ pub struct Visibility {
 #[derive(Debug)]
 pub struct NewProof {
     pub conclusion : String,
+    /// Every conclusion must be reached, rather than any one of them.
+    pub require_all: bool,
     pub premises   : String,
     pub error      : String,
-    pub rules      : [bool; 6],
+    pub rules      : [bool; rulesets::ALL_RULESETS.len()],
+    /// Use the custom ruleset from [`Preferences`] instead of the built-in rulesets.
+    pub custom     : bool,
+    /// Hide the modal rulesets, per [`Preferences::basic`].
+    pub basic      : bool,
+    /// The letters the premises and conclusion are previewed with, per [`Preferences::alphabet`].
+    pub alphabet   : Alphabet,
+    /// Whether there's a proof open, whose rulesets could be changed in place.
+    pub editing    : bool,
     pub ready      : bool,
+    /// Apply the selected rulesets to the open proof, rather than starting a new one.
+    pub apply      : bool,
 }
 
 impl NewProof {
-    pub fn try_create(&mut self) -> Option<ProofUi> {        
-        let mut checker = Checker::new();
+    /// Build a checker for the selected rulesets.
+    pub fn checker(&self, prefs: &Preferences) -> Checker {
+        match self.custom {
+            true  => Checker::from_rule_ids(&prefs.custom_rules),
+            false => Checker::from_rulesets(&self.rules)
+        }
+    }
+
+    pub fn try_create(&mut self, prefs: &Preferences) -> Option<ProofUi> {        
         let mut lines = Vec::new();
+        let alphabet = prefs.alphabet();
 
         let premises: Vec<_> = self
             .premises
             .split(',')
             .map(str::trim)
-            .map(str::to_owned)
+            .map(normalize_ops)
             .filter(|s| !s.is_empty() )
             .collect();
 
         if !self.premises.trim().is_empty() {
             for (i, premise) in premises.iter().enumerate() {
-                if let Err(e) = Sentence::parse(premise) {
+                if let Err(e) = Sentence::is_wff_with(premise, alphabet) {
                     self.error = format!("Premise {} is not well formed ({e})", i + 1);
                     return None;
                 }
@@ -58,27 +84,90 @@ impl NewProof {
                 lines.push(line);
             }
         } else {
+            // Theorems start from a blank depth-0 line, which can be
+            // justified directly or replaced by an opening subproof.
             lines.push(
-                LineUi::new(true, 1)
+                LineUi::new(false, 0)
             );
         }
 
-        if let Err(e) = Sentence::parse(&self.conclusion) {
-            self.error = format!("Conclusion is not well formed ({e})");
-            return None;
+        // Several acceptable conclusions can be given, separated by semicolons -
+        // or none at all, to explore without a fixed goal.
+        let conclusions: Vec<_> = self
+            .conclusion
+            .split(';')
+            .map(str::trim)
+            .map(normalize_ops)
+            .filter(|s| !s.is_empty() )
+            .collect();
+
+        for (i, conclusion) in conclusions.iter().enumerate() {
+            if let Err(e) = Sentence::is_wff_with(conclusion, alphabet) {
+                self.error = match conclusions.len() {
+                    1 => format!("Conclusion is not well formed ({e})"),
+                    _ => format!("Conclusion {} is not well formed ({e})", i + 1)
+                };
+                return None;
+            }
         }
 
-        for (i, rule) in self.rules.iter().enumerate() {
-            if *rule {
-                checker.add_ruleset(rulesets::ALL_RULESETS[i])
+        let checker = self.checker(prefs);
+
+        // Anything follows from contradictory premises, which usually means one was mistyped.
+        let parsed: Vec<_> = premises
+            .iter()
+            .map(|p| Sentence::parse_with(p, alphabet).expect("Premises should have been validated") )
+            .collect();
+
+        let mut output = vec![];
+
+        if Sentence::jointly_satisfiable(&parsed) == Some(false) {
+            output.push("Warning: your premises are contradictory, so any conclusion follows from them. Check that they were typed correctly.".to_string());
+        }
+
+        // A counterexample makes the premises true and the goal false - that is, every
+        // alternative conclusion false, or any one of several required conclusions.
+        // Modal arguments (and very large ones) can't be checked, and are let through silently.
+        if prefs.validity && !conclusions.is_empty() {
+            let goals: Vec<_> = conclusions
+                .iter()
+                .map(|c| Sentence::parse_with(c, alphabet).expect("Conclusions should have been validated") )
+                .collect();
+
+            let found = match self.require_all {
+                true  => goals
+                    .iter()
+                    .find_map(|g| Sentence::counterexample(&parsed, g).flatten() ),
+                false => {
+                    let mut sentences = parsed.clone();
+                    sentences.extend( goals.iter().map(Sentence::negated) );
+                    Sentence::satisfying_assignment(&sentences).flatten()
+                }
+            };
+
+            if let Some(assignment) = found {
+                let row: Vec<_> = assignment
+                    .iter()
+                    .map(|(a, v)| format!("{a} {}", if *v { "true" } else { "false" }))
+                    .collect();
+
+                output.push(format!(
+                    "Warning: this argument is invalid, so it can't be proven. Counterexample: {}. You can still work on it to see where it fails.",
+                    row.join(", ")
+                ));
             }
         }
 
         let new_ui = ProofUi {
             premises: premises.clone(),
-            conclusion: self.conclusion.clone(),
+            conclusions,
+            require_all: self.require_all,
+            rules: self.rules,
+            custom: self.custom,
             checker,
             lines,
+            alphabet,
+            output,
             ..Default::default()
         };
 
@@ -96,10 +185,16 @@ impl Default for NewProof {
     fn default() -> Self {
         Self {
             conclusion: String::new(),
+            require_all: false,
             premises: String::new(),
             error: String::new(),
-            rules: [true, false, false, false, false, false],
+            rules: [true, false, false, false, false, false, false],
+            custom: false,
+            basic: false,
+            alphabet: Alphabet::Uppercase,
+            editing: false,
             ready: false,
+            apply: false,
         }
     }
 }
@@ -108,35 +203,65 @@ impl Widget for &mut NewProof {
     fn ui(self, ui: &mut Ui) -> Response {
         self.rules[0] = true;
 
+        // Modal rulesets can't be picked in basic mode, so they can't stay enabled out of sight either.
+        if self.basic {
+            rulesets::disable_ruleset(&mut self.rules, 2);
+        }
+
         let font = FontId::new(
             15.0,
             FontFamily::Name( "math".into() )
         );
 
         ui.horizontal(|ui| {
-            ui.vertical(|ui| {
-                ui.checkbox(&mut self.rules[0], "Basic TFL");
-                ui.checkbox(&mut self.rules[1], "Derived TFL");
-                ui.checkbox(&mut self.rules[2], "System K");
-            });
+            let mut toggled = None;
+
+            let custom = self.custom;
+
+            let mut checkbox = |ui: &mut Ui, i: usize| {
+                let r = ui.add_enabled(
+                    !custom,
+                    Checkbox::new(&mut self.rules[i], rulesets::RULESET_NAMES[i])
+                );
+
+                if r.changed() {
+                    toggled = Some(i);
+                }
+            };
+
+            if self.basic {
+                ui.vertical(|ui| {
+                    checkbox(ui, 0);
+                    checkbox(ui, 1);
+                });
+            } else {
+                ui.vertical(|ui| {
+                    checkbox(ui, 0);
+                    checkbox(ui, 1);
+                    checkbox(ui, 2);
+                });
+
+                ui.vertical(|ui| {
+                    checkbox(ui, 3);
+                    checkbox(ui, 4);
+                    checkbox(ui, 5);
+                });
+            }
 
             ui.vertical(|ui| {
-                ui.checkbox(&mut self.rules[3], "System T");
-                ui.checkbox(&mut self.rules[4], "System S4");
-                ui.checkbox(&mut self.rules[5], "System S5");
+                checkbox(ui, 6);
+
+                ui.checkbox(&mut self.custom, "Custom")
+                    .on_hover_text("Use the custom ruleset defined in Preferences.");
             });
 
-            let highest = self
-                .rules
-                .iter()
-                .enumerate()
-                .rev()
-                .find(|(_, v)| **v)
-                .map(|(i, _)| i)
-                .unwrap_or_default();
-
-            for i in 0..highest {
-                self.rules[i] = true;
+            // Checking a ruleset enables its dependencies, while unchecking one
+            // disables everything that depends on it. Basic TFL is always enabled.
+            if let Some(i) = toggled {
+                match self.rules[i] || i == 0 {
+                    true  => rulesets::enable_ruleset(&mut self.rules, i),
+                    false => rulesets::disable_ruleset(&mut self.rules, i)
+                }
             }
 
             ui.separator();
@@ -166,30 +291,120 @@ impl Widget for &mut NewProof {
 
                 if c
                     .response
-                    .on_hover_text("Proof conclusion")
+                    .on_hover_text("Proof conclusion (separate several with semicolons, or leave empty for no fixed goal)")
                     .changed() 
                 {
                     self.conclusion = normalize_ops(&self.conclusion)
                 }
 
+                // Show how the input will be read, so mistyped shorthands stand out before the proof is created.
+                let show_preview = |ui: &mut Ui, label: &str, src: &str, sep: char| {
+                    let pieces = preview(src, sep, self.alphabet);
+
+                    if pieces.is_empty() {
+                        return;
+                    }
+
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label( RichText::new(label).weak() );
+
+                        for (i, piece) in pieces.into_iter().enumerate() {
+                            if i > 0 {
+                                ui.label( RichText::new(format!("{sep}")).weak() );
+                            }
+
+                            match piece {
+                                Ok(s)  => ui.label( RichText::new(s).font(font.clone()) ),
+                                Err(s) => ui
+                                    .label( RichText::new(s).font(font.clone()).color(ui.visuals().error_fg_color) )
+                                    .on_hover_text("Not well formed"),
+                            };
+                        }
+                    });
+                };
+
+                show_preview(ui, "Premises:", &self.premises, ',');
+                show_preview(ui, "Conclusion:", &self.conclusion, ';');
+
+                ui.checkbox(&mut self.require_all, "Require all conclusions")
+                    .on_hover_text("Every conclusion must be reached for the proof to be complete, rather than any one of them.");
+
                 ui.label(&self.error);
             });
         });
 
         ui.separator();
         
-        if ui.button("Create proof").clicked() {
-            self.ready = true;
-        }
+        ui.horizontal(|ui| {
+            if ui.button("Create proof").clicked() {
+                self.ready = true;
+            }
+
+            if self.editing && ui
+                .button("Change rulesets")
+                .on_hover_text("Switch the open proof to the selected rulesets, keeping its lines. The premises and conclusion above are ignored.")
+                .clicked()
+            {
+                self.apply = true;
+            }
+        });
 
         super::dummy_response(ui)
     }
 }
 
+/// Read each `sep`-separated sentence in `src` back out in canonical form,
+/// or as it was typed if it isn't well formed. Blank entries are skipped.
+fn preview(src: &str, sep: char, alphabet: Alphabet) -> Vec<Result<String, String>> {
+    src
+        .split(sep)
+        .map(str::trim)
+        .filter(|s| !s.is_empty() )
+        .map(|s| match Sentence::parse_with(s, alphabet) {
+            Ok(parsed) => Ok( parsed.to_string() ),
+            Err(_)     => Err( s.to_owned() )
+        })
+        .collect()
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Preferences {
-    pub dark_mode : bool,
-    pub ui_scale  : usize,
+    pub dark_mode   : bool,
+    pub ui_scale    : usize,
+    /// Show the parse tree of a sentence when hovering over it.
+    #[serde(default)]
+    pub parse_trees : bool,
+    /// Tint the main connective of each sentence in the proof.
+    #[serde(default)]
+    pub main_ops    : bool,
+    /// Wrap long sentences onto several rows.
+    #[serde(default)]
+    pub wrap        : bool,
+    /// The IDs of the rules in the user's custom ruleset.
+    #[serde(default)]
+    pub custom_rules: Vec<String>,
+    /// The index of the alphabet (in [`ALPHABETS`]) new proofs draw their atomic sentences from.
+    #[serde(default)]
+    pub alphabet    : usize,
+    /// Hide modal logic from new proofs and the sidebar, for beginners.
+    #[serde(default)]
+    pub basic       : bool,
+    /// Hide the rule reference sidebar, giving the proof the whole window.
+    #[serde(default)]
+    pub hide_rules  : bool,
+    /// Check that new (truth-functional) arguments are valid before starting on them.
+    #[serde(default)]
+    pub validity    : bool,
+}
+
+impl Preferences {
+    /// The alphabet new proofs draw their atomic sentences from.
+    pub fn alphabet(&self) -> Alphabet {
+        ALPHABETS
+            .get(self.alphabet)
+            .map(|(a, _)| *a)
+            .unwrap_or_default()
+    }
 }
 
 impl Widget for &mut Preferences {
@@ -219,6 +434,71 @@ impl Widget for &mut Preferences {
             }
         });
 
+        ui.separator();
+
+        ui.checkbox(&mut self.parse_trees, "Show parse trees on hover")
+            .on_hover_text("Hovering over a well-formed sentence will show how it was parsed, including its main connective.");
+
+        ui.checkbox(&mut self.main_ops, "Highlight main connectives")
+            .on_hover_text("The main connective of each well-formed sentence will be shown in a different color.");
+
+        ui.checkbox(&mut self.wrap, "Wrap long sentences")
+            .on_hover_text("Sentences too long to fit beside their citations will continue on the next row.");
+
+        ui.checkbox(&mut self.hide_rules, "Hide rule reference")
+            .on_hover_text("Hide the sidebar of rules and shorthands, giving the proof more room. It can also be toggled from the menu bar.");
+
+        ui.checkbox(&mut self.validity, "Check arguments for validity")
+            .on_hover_text("New proofs of invalid arguments will start with a warning and a counterexample. Modal arguments can't be checked this way, and are skipped.");
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Sentence letters: ");
+
+            egui::ComboBox::new("alphabet_combo", "")
+                .show_index(
+                    ui,
+                    &mut self.alphabet,
+                    ALPHABETS.len(),
+                    |i| ALPHABETS[i].1.to_string()
+                )
+                .on_hover_text("Which letters can be used as atomic sentences. Applies to new proofs.");
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Experience level: ");
+
+            ui.selectable_value(&mut self.basic, true, "Basic")
+                .on_hover_text("Only propositional logic. Modal rulesets are hidden.");
+
+            ui.selectable_value(&mut self.basic, false, "Advanced")
+                .on_hover_text("Everything, including modal logic.");
+        });
+
+        ui.separator();
+
+        ui.collapsing("Custom ruleset", |ui| {
+            ui.label("Rules to enable when starting a proof with the \"Custom\" ruleset:");
+
+            for (name, ruleset) in rulesets::RULESET_NAMES.iter().zip(rulesets::ALL_RULESETS) {
+                ui.label( RichText::new(*name).strong() );
+
+                ui.horizontal_wrapped(|ui| {
+                    for (id, _) in *ruleset {
+                        let mut enabled = self.custom_rules.iter().any(|r| r == id);
+
+                        if ui.checkbox(&mut enabled, *id).changed() {
+                            match enabled {
+                                true  => self.custom_rules.push( id.to_string() ),
+                                false => self.custom_rules.retain(|r| r != id)
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
         super::dummy_response(ui)
     }
 }
@@ -227,9 +507,35 @@ impl Default for Preferences {
     fn default() -> Self {
         // On WASM, bump the default UI scale to 125% as most browsers will be fullscreened.
         if cfg!(target_arch = "wasm32") {
-            Self { dark_mode: true, ui_scale: 1 }
+            Self { dark_mode: true, ui_scale: 1, parse_trees: false, main_ops: false, wrap: false, custom_rules: vec![], alphabet: 0, basic: false, hide_rules: false, validity: false }
         } else {
-            Self { dark_mode: true, ui_scale: 0 }
+            Self { dark_mode: true, ui_scale: 0, parse_trees: false, main_ops: false, wrap: false, custom_rules: vec![], alphabet: 0, basic: false, hide_rules: false, validity: false }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preview() {
+        assert_eq!(
+            super::preview("A -> B,  ~(A&B), ", ',', Alphabet::Uppercase),
+            vec![Ok( String::from("A → B") ), Ok( String::from("¬(A ∧ B)") )]
+        );
+
+        // Shorthands are shown as typed when the sentence doesn't parse.
+        assert_eq!(
+            super::preview("A ->; p v q", ';', Alphabet::Uppercase),
+            vec![Err( String::from("A ->") ), Err( String::from("p v q") )]
+        );
+
+        assert_eq!(
+            super::preview("p v q", ';', Alphabet::Lowercase),
+            vec![Ok( String::from("p ∨ q") )]
+        );
+
+        assert!( super::preview(" , ", ',', Alphabet::Uppercase).is_empty() );
+    }
+}
@@ -1,8 +1,11 @@
+use std::ops::RangeInclusive;
+
 use egui::*;
 
-use crate::check::Checker;
+use crate::check::{rulesets, Checker, LineReport, ProofOutcome};
 
-use crate::parse::Proof;
+use crate::parse::{Alphabet, Proof, Sentence};
+use crate::parse::{Citation, LineNumber};
 use crate::parse::normalize_ops;
 
 const LINE_NUMBER_FONT_SIZE : f32 = 15.0;
@@ -13,6 +16,13 @@ const LEFT_LINE_HORI_PAD    : f32 = LINE_NUMBER_HORI_PAD + 5.0;
 const SUBPROOF_INDENTATION  : f32 = 15.0;
 const SUBPROOF_LINE_PAD     : f32 = 5.0;
 const SENTENCE_CITATION_PAD : f32 = 10.0;
+const MINIMAP_WIDTH         : f32 = 40.0;
+const MINIMAP_INDENTATION   : f32 = 4.0;
+const STATUS_GUTTER_WIDTH   : f32 = 20.0;
+
+/// Proofs longer than this are only checked on request, so that
+/// editing them doesn't stall every frame.
+const LIVE_CHECK_LINE_LIMIT : usize = 500;
 
 #[derive(Debug, Default)]
 pub struct LineUi {
@@ -27,7 +37,8 @@ impl LineUi {
         let mut citation = String::new();
 
         if premise {
-            citation = "PR".to_string()
+            // Subproofs are opened by assumptions, not premises.
+            citation = if depth > 0 { "AS" } else { "PR" }.to_string()
         }
         
         Self {
@@ -39,21 +50,385 @@ impl LineUi {
     }
 }
 
+/// Describe line `n` for screen readers, e.g. "Line 3, assumption level 2".
+fn line_label(n: usize, line: &LineUi) -> String {
+    match (line.premise, line.depth) {
+        (true, 0)  => format!("Line {n}, premise"),
+        (true, d)  => format!("Line {n}, opens assumption level {d}"),
+        (false, 0) => format!("Line {n}"),
+        (false, d) => format!("Line {n}, assumption level {d}"),
+    }
+}
+
+/// A piece of the instructions above a proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Instruction {
+    /// Plain prose.
+    Text(&'static str),
+    /// A premise or conclusion, set in the math font.
+    Sentence(String),
+    /// The "therefore" sign before the conclusion(s).
+    Therefore,
+}
+
+/// Break the instructions for a proof with the given premises and (alternative) conclusions into pieces,
+/// so that each sentence can be laid out (and wrapped onto a new row) on its own.
+/// 
+/// With `all` set, every conclusion must be reached, rather than any one of them.
+fn instructions(premises: &[String], conclusions: &[String], all: bool) -> Vec<Instruction> {
+    use Instruction as I;
+
+    let list = |items: &[String], sep| {
+        items
+            .iter()
+            .enumerate()
+            .flat_map(|(i, s)| {
+                let sep = (i + 1 < items.len()).then_some( I::Text(sep) );
+                std::iter::once( I::Sentence(s.clone()) ).chain(sep)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut out = vec![];
+
+    match (premises.is_empty(), conclusions.len()) {
+        (true, 0) => out.push( I::Text("Construct a proof (no fixed goal)") ),
+        (false, 0) => {
+            out.push( I::Text("Explore the consequences of ") );
+            out.extend( list(premises, ", ") );
+            out.push( I::Text(" (no fixed goal)") );
+        }
+        (premise_free, n) => {
+            out.push(match (premise_free, n, all) {
+                (false, _, _)    => I::Text("Construct a proof for the argument "),
+                (true, 1, _)     => I::Text("Construct a proof for the theorem "),
+                (true, _, false) => I::Text("Construct a proof for any of the theorems "),
+                (true, _, true)  => I::Text("Construct a proof for all of the theorems "),
+            });
+
+            if !premise_free {
+                out.extend( list(premises, ", ") );
+                out.push( I::Text(" ") );
+            }
+
+            out.push( I::Therefore );
+
+            match (premise_free, n, all) {
+                (false, 2.., false) => out.push( I::Text(" one of ") ),
+                (false, 2.., true)  => out.push( I::Text(" all of ") ),
+                _                   => out.push( I::Text(" ") )
+            }
+
+            out.extend( list(conclusions, "; ") );
+        }
+    }
+
+    out
+}
+
+/// Lay out a sentence, tinting its main connective (if any) with `highlight`
+/// and wrapping it onto new rows past `max_width`.
+fn sentence_job(text: &str, font: &FontId, color: Color32, highlight: Color32, max_width: f32) -> text::LayoutJob {
+    let mut job = text::LayoutJob::default();
+    job.wrap.max_width = max_width;
+    let format = |color| TextFormat::simple(font.clone(), color);
+
+    // Operator offsets are relative to the normalized sentence, so
+    // text that's mid-edit (e.g. a half-typed "->") is left alone.
+    let main_op = Sentence::main_operator(text).filter(|_| normalize_ops(text) == text);
+
+    match main_op {
+        Some((c, n)) => {
+            let start = text
+                .char_indices()
+                .nth(n)
+                .map(|(p, _)| p)
+                .expect("Main operator should be in bounds");
+
+            let end = start + c.len_utf8();
+
+            job.append(&text[..start], 0.0, format(color));
+            job.append(&text[start..end], 0.0, format(highlight));
+            job.append(&text[end..], 0.0, format(color));
+        }
+        None => job.append(text, 0.0, format(color))
+    }
+
+    job
+}
+
+/// Returns the index of the last line in the block started by line `n` -
+/// the entire subproof if `n` is a subproof premise, or `n` itself otherwise.
+fn block_end(lines: &[LineUi], n: usize) -> usize {
+    let LineUi { premise, depth, .. } = lines[n];
+
+    if !premise || depth == 0 {
+        return n;
+    }
+
+    let mut end = n;
+
+    for (i, line) in lines.iter().enumerate().skip(n + 1) {
+        if (line.premise && line.depth == depth) || line.depth < depth {
+            break;
+        }
+
+        end = i;
+    }
+
+    end
+}
+
+/// How much of the window's width the proof panel gets.
+/// 
+/// Worked out once per frame and passed down, so that the proof body, its line controls and the
+/// checker output can't disagree (e.g. by each reading the window's width at a different point mid-resize.)
+#[derive(Debug, Clone, Copy)]
+struct Widths {
+    /// Where the proof area - and so its line controls - ends.
+    proof  : f32,
+    /// How wide the checker output can be.
+    output : f32,
+}
+
+impl Widths {
+    /// The widths for a window `window` wide, with the proof taking up the whole window
+    /// (rather than sharing it with the rule reference) if `wide`.
+    fn new(window: f32, wide: bool) -> Self {
+        let fraction = match wide {
+            true  => 0.95,
+            false => 0.95 - super::REFERENCE_FRACTION
+        };
+
+        Self {
+            proof  : window * fraction,
+            output : window * (fraction + 0.05),
+        }
+    }
+}
+
+/// Whether each line is justified, according to the reports of a check. Premises are always valid.
+fn line_status(lines: &[LineUi], reports: &[LineReport]) -> Vec<Option<bool>> {
+    lines
+        .iter()
+        .zip(reports)
+        .map(|(l, r)| Some( l.premise || r.passed() ) )
+        .collect()
+}
+
+/// Returns the indices of the premises and assumptions in force at line `n` -
+/// the premises of the proof, then the assumptions opening each subproof around it, from the outside in.
+fn in_scope(lines: &[LineUi], n: usize) -> Vec<usize> {
+    let mut scope = vec![];
+
+    // The depth whose opening assumptions are being looked for, and whether they've been found yet.
+    let mut depth  = lines[n].depth;
+    let mut opened = false;
+
+    for (i, line) in lines[..=n].iter().enumerate().rev() {
+        // Lines in closed subproofs (or sibling subproofs) aren't in scope.
+        if line.depth > depth {
+            continue;
+        }
+
+        if line.depth < depth {
+            depth  = line.depth;
+            opened = false;
+        }
+
+        if line.premise {
+            scope.push(i);
+            opened = true;
+        } else if opened && depth > 0 {
+            // Past the assumptions that opened this subproof, so move out to the one around it.
+            depth -= 1;
+            opened = false;
+        }
+    }
+
+    scope.reverse();
+    scope
+}
+
+/// One of the lines drawn to show the structure of a proof, positioned by line index and depth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Bar {
+    /// A vertical line at `depth`, running alongside lines `start..=end`.
+    Scope { depth: u16, start: usize, end: usize },
+    /// A horizontal line at `depth`, under the premises or assumption that end on `line`.
+    Assumptions { depth: u16, line: usize },
+}
+
+impl Bar {
+    /// The vertical extent of the bar, for lines laid out as in `rows`.
+    /// 
+    /// Scopes run from the top of their first line to halfway into the padding below their last,
+    /// so a scope continues unbroken past its nested subproofs, but two sibling subproofs
+    /// at the same depth are separated by the other half of that padding.
+    /// Assumption lines sit where a scope ending on the same line would stop.
+    fn y_span(&self, rows: &Rows) -> RangeInclusive<f32> {
+        let top    = |n: usize| rows.top(n);
+        let bottom = |n: usize| rows.top(n) + rows.height(n) + LINE_NUMBER_VERT_PAD / 2.0;
+
+        match *self {
+            Self::Scope { start, end, .. } => top(start)..=bottom(end),
+            Self::Assumptions { line, .. } => bottom(line)..=bottom(line),
+        }
+    }
+}
+
+/// The vertical layout of the lines of a proof - where each one starts, and how tall it is.
+/// 
+/// Lines are normally all as tall as a line number, but wrapped sentences can take up several rows.
+#[derive(Debug, Clone, PartialEq)]
+struct Rows {
+    tops    : Vec<f32>,
+    heights : Vec<f32>,
+}
+
+impl Rows {
+    /// Stack lines of the given heights one after another, below a header `h` high.
+    fn new(h: f32, heights: Vec<f32>) -> Self {
+        let mut y = h + LINE_NUMBER_VERT_PAD;
+
+        let tops = heights
+            .iter()
+            .map(|height| {
+                let top = y;
+                y += height + LINE_NUMBER_VERT_PAD;
+                top
+            })
+            .collect();
+
+        Self { tops, heights }
+    }
+
+    fn top(&self, n: usize) -> f32 {
+        self.tops[n]
+    }
+
+    fn height(&self, n: usize) -> f32 {
+        self.heights[n]
+    }
+
+    /// Where the line after the last one would start, below a header `h` high.
+    fn end(&self, h: f32) -> f32 {
+        match self.tops.last() {
+            Some(top) => top + self.heights.last().copied().unwrap_or(h) + LINE_NUMBER_VERT_PAD,
+            None      => h + LINE_NUMBER_VERT_PAD
+        }
+    }
+}
+
+/// Lay out the bars for a proof - one scope for the proof as a whole and one for each subproof,
+/// each with its premises or assumption underlined.
+fn bars(lines: &[LineUi]) -> Vec<Bar> {
+    let mut bars = vec![];
+
+    if lines.is_empty() {
+        return bars;
+    }
+
+    bars.push(Bar::Scope { depth: 0, start: 0, end: lines.len() - 1 });
+
+    let premises = lines
+        .iter()
+        .take_while(|l| l.premise && l.depth == 0)
+        .count();
+
+    if premises > 0 {
+        bars.push(Bar::Assumptions { depth: 0, line: premises - 1 });
+    }
+
+    for (i, line) in lines.iter().enumerate().filter(|(_, l)| l.premise && l.depth > 0) {
+        bars.push(Bar::Scope { depth: line.depth, start: i, end: block_end(lines, i) });
+        bars.push(Bar::Assumptions { depth: line.depth, line: i });
+    }
+
+    bars
+}
+
+/// Drag-and-drop payload carrying the index of the line being moved.
+struct MoveLine(usize);
+
 #[derive(Default)]
 pub struct ProofUi {
-    pub conclusion : String,
+    pub conclusions: Vec<String>,
+    /// Every conclusion must be reached, rather than any one of them.
+    pub require_all: bool,
     pub premises   : Vec<String>,
     pub lines      : Vec<LineUi>,
     pub output     : Vec<String>,
     pub focus_to   : Option<usize>,
+    /// A line to scroll into the middle of the view on the next frame.
+    pub pan_to     : Option<usize>,
     pub current    : Option<usize>,
     pub checker    : Checker,
+    /// The rulesets (as flags for each of [`rulesets::ALL_RULESETS`]) the checker was built from...
+    pub rules      : [bool; rulesets::ALL_RULESETS.len()],
+    /// ...unless it was built from the custom ruleset instead.
+    pub custom     : bool,
     pub updated    : bool,
+    /// Check the proof on the next frame, even if it's too large for live checking.
+    pub check_now  : bool,
     pub transform  : emath::TSTransform,
+    pub show_trees : bool,
+    /// Tint the main connective of each well-formed sentence.
+    pub main_ops   : bool,
+    /// Wrap long sentences onto several rows, rather than letting them run off to the side.
+    pub wrap       : bool,
+    /// Whether the proof has the whole window to itself (i.e. the rule reference is hidden.)
+    pub wide       : bool,
+    /// The window's width this frame, as read by the app (and used for the rule reference too.)
+    pub window     : f32,
+    /// A line that would reach the conclusion, found on request.
+    pub hint       : Option<LineUi>,
+    /// The letters that can be used as atomic sentences.
+    pub alphabet   : Alphabet,
+    /// Accept `*` in citations, and search for lines or subproofs that could take its place.
+    pub explore    : bool,
+    /// While replaying, how many lines of the proof have been revealed so far.
+    pub replay     : Option<usize>,
+    /// The verdict on the line being edited, as its index, whether it passed, and an explanation.
+    pub line_check : Option<(usize, bool, String)>,
+    /// Whether each line passed the last check, or `None` where it couldn't be checked.
+    pub status     : Vec<Option<bool>>,
+    /// Messages to show below the checker's output once the proof has been re-checked (e.g. the report from Clean Up.)
+    pub notes      : Vec<String>,
 }
 
 impl ProofUi {
-    fn draw_surroundings(&mut self, ui: &mut Ui, p: &Painter) -> (f32, f32) {
+    /// The lines currently on display - all of them, unless the proof is being replayed.
+    fn visible(&self) -> &[LineUi] {
+        let n = self.replay.unwrap_or(self.lines.len()).min(self.lines.len());
+        &self.lines[..n]
+    }
+
+    /// Switch to a different set of rulesets, keeping every line as-is.
+    /// 
+    /// Lines that cite rules which are no longer enabled will be flagged on the next check.
+    pub fn change_rules(&mut self, rules: [bool; rulesets::ALL_RULESETS.len()], custom: bool, checker: Checker) {
+        self.rules   = rules;
+        self.custom  = custom;
+        self.checker = checker;
+        self.hint    = None;
+        self.updated = true;
+    }
+
+    /// Start (or stop) replaying the proof one line at a time.
+    /// 
+    /// The proof can't be edited while it's being replayed.
+    pub fn set_replay(&mut self, on: bool) {
+        self.replay  = on.then_some(1);
+        self.hint    = None;
+        self.current = None;
+        self.updated = true;
+    }
+
+    /// Draw the instructions above the proof, wrapping them before `x_end`.
+    /// 
+    /// Returns the width of the line numbers, the height of a line, and the height of the instructions.
+    fn draw_surroundings(&mut self, ui: &mut Ui, p: &Painter, x_end: f32) -> (f32, f32, f32) {
         // Prefetch TeX mathematics font.
         let font = FontId::new(
             SENTENCE_FONT_SIZE,
@@ -69,73 +444,53 @@ impl ProofUi {
             text_color
         );
 
-        // Pull out the width and height of the largest line number.
-        let w = max.rect.width();
+        // Pull out the width and height of the largest line number,
+        // leaving room for the status gutter to its left.
+        let w = STATUS_GUTTER_WIDTH + max.rect.width();
         let h = max.rect.height();
 
-        // Init Y-axis pointer value, starting from the top of the painter area.
-        let mut y = 0.0;
-
-        // Format premises for "instructions" above the proof.
-        let mut premises = String::new();
+        // The instructions go at the very top of the painter area, in prose with the sentences
+        // set like the proof body. Each piece moves to a new row if it would run past the proof area.
+        let prose = FontId::proportional(SENTENCE_FONT_SIZE);
 
-        for premise in &self.premises {
-            premises.push_str(premise);
-            premises.push_str(", ");
-        }
+        let (mut x, mut y) = (w, 0.0);
+        let mut bottom = h;
 
-        let premises = premises
-            .trim()
-            .trim_end_matches(',');
+        for piece in instructions(&self.premises, &self.conclusions, self.require_all) {
+            let galley = match piece {
+                Instruction::Text(t)     => p.layout_no_wrap(t.to_string(), prose.clone(), text_color),
+                Instruction::Sentence(s) => p.layout_no_wrap(s, font.clone(), text_color),
+                Instruction::Therefore   => p.layout_no_wrap("∴".to_string(), font.clone(), text_color),
+            };
 
-        // Layout and render the instructions.
-        let instructions = p.layout_no_wrap(
-            format!("Construct a proof for the argument {premises} ∴ {}", self.conclusion),
-            font.clone(),
-            text_color
-        );
+            if x > w && x + galley.rect.width() > x_end {
+                x = w;
+                y = bottom;
+            }
 
-        p.galley(
-            Pos2::new(w, y),
-            instructions,
-            Color32::RED
-        );
+            let size = galley.rect.size();
 
-        let w = w + LINE_NUMBER_VERT_PAD;
+            p.galley(
+                Pos2::new(x, y),
+                galley,
+                Color32::RED
+            );
 
-        // Bump Y-axis pointer downwards.
-        y += h + LINE_NUMBER_VERT_PAD;
+            x += size.x;
+            bottom = bottom.max(y + size.y);
+        }
 
-        // If we aren't trying to prove a theorem, outline the space for our premises.
-        if !self.premises.is_empty() {
-            // Compute the width of the widest premise.
-            let mut max_width = 0.0;
-            
-            for premise in &self.premises {
-                let layout = p.layout_no_wrap(
-                    premise.to_owned(),
-                    font.clone(),
-                    text_color
-                );
-    
-                if layout.rect.width() > max_width {
-                    max_width = layout.rect.width();
-                }
-            }
+        let w = w + LINE_NUMBER_VERT_PAD;
 
-            // Fudge factor.
-            max_width += SENTENCE_CITATION_PAD;
+        // Return the computed line number width and height for use in rendering the proof body.
+        (w, h, bottom)
+    }
 
-            // Draw a horizontal line separating the premises from the body of the proof.
-            p.hline(
-                (w + LEFT_LINE_HORI_PAD)..=(w + LEFT_LINE_HORI_PAD + max_width),
-                y + (h + LINE_NUMBER_VERT_PAD) * self.premises.len() as f32 - LINE_NUMBER_VERT_PAD,
-                Stroke::new(1.0, text_color)
-            );
-        }
+    /// Render the line numbers down the left side of the proof body, `w` from the left.
+    fn draw_line_numbers(&self, ui: &Ui, p: &Painter, w: f32, rows: &Rows) {
+        let text_color = ui.visuals().strong_text_color();
 
-        // Render the line numbers down the left side of the proof body.
-        for (i, _) in self.lines.iter().enumerate() {
+        for (i, _) in self.visible().iter().enumerate() {
             let mut text = text::LayoutJob::simple_singleline(
                 format!("{}", i + 1),
                 FontId::monospace(15.0),
@@ -147,59 +502,81 @@ impl ProofUi {
             text.halign = Align::RIGHT;
 
             p.galley(
-                Pos2::new(w + LINE_NUMBER_HORI_PAD, y),
+                Pos2::new(w + LINE_NUMBER_HORI_PAD, rows.top(i)),
                 p.layout_job(text),
                 Color32::RED
             );
-
-            // Bump y-axis pointer.
-            y += h + LINE_NUMBER_VERT_PAD;
         }
+    }
 
-        // Draw leftmost vertical line, separating the line numbers from the proof.
-        p.vline(
-            w + LEFT_LINE_HORI_PAD, 
-            0.0 + (h + LINE_NUMBER_VERT_PAD)..=(y - LINE_NUMBER_VERT_PAD),
-            Stroke::new(1.0, text_color)
-        );
+    /// Render a check or cross in the gutter left of each line number, per [`status`](ProofUi::status).
+    fn draw_status(&self, ui: &Ui, p: &Painter, rows: &Rows) {
+        for (i, status) in self.status.iter().enumerate().take( self.visible().len() ) {
+            let (glyph, color) = match status {
+                Some(true)  => ("✔", Color32::GREEN),
+                Some(false) => ("✖", ui.visuals().error_fg_color),
+                None        => continue
+            };
 
-        // Return the computed line number width and height for use in rendering the proof body.
-        (w, h)
+            p.galley(
+                Pos2::new(0.0, rows.top(i)),
+                p.layout_no_wrap(glyph.to_string(), FontId::proportional(LINE_NUMBER_FONT_SIZE), color),
+                Color32::RED
+            );
+        }
     }
 
     fn draw_linectl(&mut self, n: usize, ui: &mut Ui) {
         let premise = self.lines[n].premise;
         let depth   = self.lines[n].depth;
 
-        // The delete line button is available everywhere except the starting premises.
+        // The delete line button is available everywhere except the starting premises
+        // and the sole remaining line of a theorem.
         #[allow(clippy::nonminimal_bool)]
-        if !(premise && depth == 0) && !(self.premises.is_empty() && n == 0) && ui.button("X")
+        if !(premise && depth == 0) && !(self.premises.is_empty() && self.lines.len() == 1) && ui.button("X")
             .on_hover_text("Remove this line")
             .clicked()
         {
-            if premise {
-                let mut end = n;
+            let end = self.block_end(n);
+            self.lines.drain(n..=end);
+
+            // Removing a leading subproof from a theorem can empty the proof,
+            // so make sure there's always a line to work from.
+            if self.lines.is_empty() {
+                self.lines.push(
+                    LineUi::new(false, 0)
+                );
+            }
 
-                for i in (n + 1)..self.lines.len() {
-                    end = i;
-    
-                    if (self.lines[i].premise && self.lines[i].depth == depth) || self.lines[i].depth < depth {
-                        break;
-                    }
-                }
+            self.updated = true;
+        }
 
-                if end == n {
-                    self.lines.remove(n);
-                } else {
-                    self.lines.drain(n..=end); 
-                }
+        // The move handle is available everywhere except the starting premises.
+        if !(premise && depth == 0) {
+            let handle = ui.button("MV")
+                .on_hover_text("Drag to move this line (or the subproof it opens)")
+                .interact(Sense::drag());
+
+            if handle.drag_started() {
+                DragAndDrop::set_payload(ui.ctx(), MoveLine(n));
             }
-            else {
-                self.lines.remove(n);
+        }
+
+        // The duplicate button is available everywhere except the starting premises.
+        if !(premise && depth == 0) && ui.button("Dup")
+            .on_hover_text("Copy this line's sentence into a new line below it")
+            .clicked()
+            {
+                self.duplicate_line(n);
             }
 
-            self.updated = true;
-        }
+        // The copy button is available on subproof premises.
+        if premise && depth > 0 && ui.button("Cp")
+            .on_hover_text("Copy this subproof to the clipboard, in Carnap's syntax")
+            .clicked()
+            {
+                self.copy_subproof(n, ui.ctx());
+            }
 
         // The new line below button is universal.
         if ui.button("NL")
@@ -217,6 +594,21 @@ impl ProofUi {
                 self.insert_line(n, true, depth + 1);
             }
 
+        // Indentation controls are only shown when the result keeps the proof's structure legal.
+        if self.can_outdent(n) && ui.button("<")
+            .on_hover_text("Move this line (or the subproof it opens) one level shallower")
+            .clicked()
+            {
+                self.shift_block(n, false);
+            }
+
+        if self.can_indent(n) && ui.button(">")
+            .on_hover_text("Move this line (or the subproof it opens) one level deeper")
+            .clicked()
+            {
+                self.shift_block(n, true);
+            }
+
         let (n_premise, n_depth) = self
             .lines
             .get(n + 1)
@@ -240,6 +632,171 @@ impl ProofUi {
         }
     }
 
+    /// Returns the index of the last line in the block started by line `n` -
+    /// the entire subproof if `n` is a subproof premise, or `n` itself otherwise.
+    fn block_end(&self, n: usize) -> usize {
+        block_end(&self.lines, n)
+    }
+
+    /// Returns whether the block started by line `n` can be indented one level.
+    /// 
+    /// A subproof premise can sit at most one level deeper than the line before it, 
+    /// while any other line must continue a subproof that is already open.
+    fn can_indent(&self, n: usize) -> bool {
+        let LineUi { premise, depth, .. } = self.lines[n];
+
+        if (premise && depth == 0) || n == 0 {
+            return false;
+        }
+
+        let prev = self.lines[n - 1].depth;
+
+        match premise {
+            true  => prev >= depth,
+            false => prev > depth
+        }
+    }
+
+    /// Returns whether the block started by line `n` can be outdented one level.
+    /// 
+    /// Subproof premises cannot be moved to depth zero, and outdenting a line
+    /// must not orphan the deeper lines that follow it.
+    fn can_outdent(&self, n: usize) -> bool {
+        let LineUi { premise, depth, .. } = self.lines[n];
+
+        if premise {
+            return depth > 1;
+        }
+
+        if depth == 0 {
+            return false;
+        }
+
+        match self.lines.get(n + 1) {
+            Some(next) => next.depth < depth || (next.premise && next.depth == depth),
+            None => true
+        }
+    }
+
+    /// Shift the depth of the block started by line `n` up or down by one level.
+    fn shift_block(&mut self, n: usize, deeper: bool) {
+        let end = self.block_end(n);
+
+        for line in &mut self.lines[n..=end] {
+            match deeper {
+                true  => line.depth += 1,
+                false => line.depth -= 1
+            }
+        }
+
+        self.updated = true;
+    }
+
+    /// Move the block started by line `src` so that it sits directly below line `dst`,
+    /// re-indenting it to fit its new surroundings and renumbering citations to follow.
+    pub fn move_block(&mut self, src: usize, dst: usize) {
+        let end = self.block_end(src);
+
+        // The starting premises are fixed in place, and nothing can be dropped among them.
+        let fixed = self
+            .lines
+            .iter()
+            .take_while(|l| l.premise && l.depth == 0)
+            .count();
+
+        if src < fixed || dst + 1 < fixed || (src..=end).contains(&dst) || dst + 1 == src {
+            return;
+        }
+
+        // Compute the depth "containing" the block, then shift it to match the drop target.
+        let base = match self.lines[src].premise {
+            true  => self.lines[src].depth - 1,
+            false => self.lines[src].depth
+        };
+
+        let target = self.lines[dst].depth;
+
+        for line in &mut self.lines[src..=end] {
+            line.depth = line.depth - base + target;
+        }
+
+        // Compute the new ordering of the old line indices.
+        let mut order: Vec<_> = (0..self.lines.len())
+            .filter(|i| !(src..=end).contains(i))
+            .collect();
+
+        let at = order
+            .iter()
+            .position(|i| *i == dst)
+            .expect("Drop target should be outside the moved block")
+            + 1;
+
+        order.splice(at..at, src..=end);
+
+        // Map old line numbers (1-indexed) to new ones.
+        let mut renumber = vec![0_u16; self.lines.len() + 1];
+
+        for (new, old) in order.iter().enumerate() {
+            renumber[old + 1] = new as u16 + 1;
+        }
+
+        let mut lines: Vec<_> = self.lines
+            .drain(..)
+            .map(Some)
+            .collect();
+
+        self.lines = order
+            .iter()
+            .map(|i| lines[*i].take().expect("Each line should be moved exactly once"))
+            .collect();
+
+        self.renumber_citations(&renumber);
+
+        self.current  = None;
+        self.focus_to = None;
+        self.updated  = true;
+    }
+
+    /// Rewrite every citation through `renumber`, which maps old line numbers (1-indexed) to new ones.
+    /// 
+    /// Lines mapped to 0 no longer exist, so any citation of one becomes a placeholder (`?`) rather
+    /// than pointing at whichever line took its number. Citations that don't change are left as typed.
+    fn renumber_citations(&mut self, renumber: &[u16]) {
+        // `None` for removed lines - numbers outside the proof never referred to a line, so they stay put.
+        let map = |n: u16| match renumber.get(n as usize) {
+            Some(0) if n > 0 => None,
+            Some(m) if n > 0 => Some(*m),
+            _                => Some(n)
+        };
+
+        for line in &mut self.lines {
+            if line.premise {
+                continue;
+            }
+
+            let Ok(mut c) = Citation::parse(&line.citation) else {
+                continue;
+            };
+
+            let cited: Option<Vec<_>> = c.l
+                .iter()
+                .map(|l| match l {
+                    LineNumber::One(n)  => map(*n).map(LineNumber::One),
+                    LineNumber::Many(r) => Some( LineNumber::Many(map(*r.start())?..=map(*r.end())?) ),
+                })
+                .collect();
+
+            match cited {
+                Some(cited) if cited == c.l => (),
+                Some(cited) => {
+                    c.l = cited;
+                    line.citation = c.to_string();
+                }
+                None => line.citation = String::from("?")
+            }
+        }
+    }
+
     pub fn insert_line(&mut self, idx: usize, premise: bool, depth: u16) {
         self.lines.insert(
             idx + 1,
@@ -249,7 +806,23 @@ impl ProofUi {
         self.focus_to = Some(idx + 1);
     }
 
-    pub fn draw(&mut self, ui: &mut Ui) {          
+    pub fn duplicate_line(&mut self, idx: usize) {
+        let line = LineUi {
+            sentence: self.lines[idx].sentence.clone(),
+            ..LineUi::new(false, self.lines[idx].depth)
+        };
+
+        self.lines.insert(
+            idx + 1,
+            line
+        );
+
+        self.focus_to = Some(idx + 1);
+        self.updated  = true;
+    }
+
+    /// Draw the proof body, with the line controls ending at `linectl_x_end`.
+    pub fn draw(&mut self, ui: &mut Ui, linectl_x_end: f32) {
         let p = ui.painter().to_owned();
 
         let font = FontId::new(
@@ -258,12 +831,27 @@ impl ProofUi {
         );
 
         let text_color = ui.visuals().strong_text_color();
+        let highlight  = ui.visuals().hyperlink_color;
         
-        let (w, h) = self.draw_surroundings(ui, &p);
+        let (w, h, header) = self.draw_surroundings(ui, &p, linectl_x_end);
+
+        let shown     = self.visible().len();
+        let replaying = self.replay.is_some();
 
-        let mut y = 0.0 + (h + LINE_NUMBER_VERT_PAD);
         let x = w + LEFT_LINE_HORI_PAD + 5.0;
 
+        // When wrapping, sentences get at most half of the proof area.
+        let wrap_width = match self.wrap {
+            true  => (linectl_x_end - x) / 2.0,
+            false => f32::INFINITY
+        };
+
+        // Main connectives are only tinted on request.
+        let highlight = match self.main_ops {
+            true  => highlight,
+            false => text_color
+        };
+
         let max_depth = self
             .lines
             .iter()
@@ -296,6 +884,34 @@ impl ProofUi {
             }
         }
 
+        sentence_max_width = f32::min(sentence_max_width, wrap_width);
+
+        // Wrapped sentences make for taller lines.
+        let rows = Rows::new(header, self
+            .visible()
+            .iter()
+            .map(|l| {
+                if !self.wrap {
+                    return h
+                }
+
+                let s = p.layout_job( sentence_job(&l.sentence, &font, text_color, highlight, sentence_max_width) );
+
+                match s.rows.len() {
+                    1 => h,
+                    _ => s.rect.height().max(h)
+                }
+            })
+            .collect()
+        );
+
+        self.draw_line_numbers(ui, &p, w, &rows);
+        self.draw_status(ui, &p, &rows);
+
+        if let Some(i) = self.pan_to.take().filter(|i| *i < shown ) {
+            self.center_on(ui.clip_rect(), rows.top(i));
+        }
+
         // Fudge factor.
         sentence_max_width += SENTENCE_CITATION_PAD;
 
@@ -312,37 +928,69 @@ impl ProofUi {
         citation_x_end += SENTENCE_CITATION_PAD;
 
         let linectl_x_start = citation_x_end;
-        let linectl_x_end   = ui.ctx().input(|i| {
-            let r = i.screen_rect().x_range();
-            r.max
-        }) * 0.70;
 
-        for (i, line) in self.lines.iter_mut().enumerate() {
-            if line.premise && line.depth == 0 {
-                let text = p.layout_no_wrap(
-                    line.sentence.clone(),
-                    font.clone(),
-                    text_color
-                );
+        let mut focused_row = None;
+
+        for (i, line) in self.lines.iter_mut().enumerate().take(shown) {
+            let y = rows.top(i);
+            let h = rows.height(i);
+
+            // Top-level premises are fixed, and nothing can be edited during a replay.
+            if replaying || (line.premise && line.depth == 0) {
+                let text = match self.main_ops || self.wrap {
+                    true  => p.layout_job( sentence_job(&line.sentence, &font, text_color, highlight, wrap_width) ),
+                    false => p.layout_no_wrap(
+                        line.sentence.clone(),
+                        font.clone(),
+                        text_color
+                    )
+                };
 
                 p.galley(
-                    Pos2::new(x, y),
+                    Pos2::new(x + SUBPROOF_INDENTATION * line.depth as f32 + 2.0, y),
                     text,
                     Color32::RED
                 );
 
-                y += h + LINE_NUMBER_VERT_PAD;
+                if !line.premise {
+                    p.galley(
+                        Pos2::new(citation_x_start, y),
+                        p.layout_no_wrap(line.citation.clone(), font.clone(), text_color),
+                        Color32::RED
+                    );
+                }
+
+                // Painted lines are invisible to screen readers, so stand in a focusable node for each.
+                let label = match line.premise {
+                    true  => format!("{}: {}", line_label(i + 1, line), line.sentence),
+                    false => format!("{}: {}, by {}", line_label(i + 1, line), line.sentence, line.citation),
+                };
+
+                ui.interact(
+                    Rect::from_two_pos(pos2(x, y), pos2(citation_x_end, y + h)),
+                    ui.id().with(("line", i)),
+                    Sense::focusable_noninteractive()
+                )
+                .widget_info(|| WidgetInfo::labeled(WidgetType::Label, &label) );
 
                 continue;
             }
 
-            let te = TextEdit::singleline(&mut line.sentence)
+            let mut layouter = |ui: &Ui, text: &str, _: f32| {
+                ui.fonts(|f| f.layout_job( sentence_job(text, &font, text_color, highlight, wrap_width) ))
+            };
+
+            let mut te = TextEdit::singleline(&mut line.sentence)
                 .font(font.clone())
                 .text_color(text_color)
                 .frame(false)
                 .margin(Margin::symmetric(0.0, 0.0))
                 .id_source((i, 1));
 
+            if self.main_ops || self.wrap {
+                te = te.layouter(&mut layouter);
+            }
+
             let mut x_start = x;
             x_start += SUBPROOF_INDENTATION * line.depth as f32;
             x_start += 2.0;
@@ -350,11 +998,21 @@ impl ProofUi {
             let mut x_end = x_start;
             x_end += sentence_max_width;
             
-            let res = ui.put(
+            let mut res = ui.put(
                 Rect::from_two_pos(Pos2::new(x_start, y), Pos2::new(x_end, y + h)),
                 te
             );
 
+            ui.ctx().accesskit_node_builder(res.id, |b| b.set_name( format!("{}, sentence", line_label(i + 1, line)) ));
+
+            if self.show_trees && res.hovered() {
+                if let Ok(s) = Sentence::parse_with(&line.sentence, self.alphabet) {
+                    res = res.on_hover_ui(|ui| {
+                        ui.label( RichText::new( s.tree_string() ).monospace() );
+                    });
+                }
+            }
+
             if res.changed() {
                 line.sentence = normalize_ops(&line.sentence);
                 self.updated = true;
@@ -362,6 +1020,7 @@ impl ProofUi {
 
             if res.has_focus() {
                 self.current = Some(i);
+                focused_row = Some( Rect::from_two_pos(pos2(x, y), pos2(citation_x_end, y + h)) );
             }
 
             if Some(i) == self.current && res.lost_focus() {
@@ -373,32 +1032,8 @@ impl ProofUi {
                 res.request_focus();
             }
 
-            // This is a premise, so no citation is needed - 
-            // just some fancy lines.
-            if line.premise {
-                let y_end = y + (h + LINE_NUMBER_VERT_PAD / 2.0);
-
-                p.vline(
-                    x + (SUBPROOF_INDENTATION * line.depth as f32) - SUBPROOF_LINE_PAD,
-                    y..=y_end,
-                    Stroke::new(1.0, text_color)
-                );
-                
-                let mut x_start = x;
-                x_start += SUBPROOF_INDENTATION * line.depth as f32;
-                x_start -= SUBPROOF_LINE_PAD;
-
-                let mut x_end = citation_x_start;
-                x_end -= SENTENCE_CITATION_PAD;
-
-                p.hline(
-                    x_start..=x_end,
-                    y + (h + LINE_NUMBER_VERT_PAD / 2.0),
-                    Stroke::new(1.0, text_color)
-                );
-            }
-            // We're making a deduction, so a citation is needed.
-            else {
+            // Assumptions don't need a citation, but deductions do.
+            if !line.premise {
                 // Create field for citation.
                 let te = TextEdit::singleline(&mut line.citation)
                     .font(font.clone())
@@ -412,6 +1047,8 @@ impl ProofUi {
                     te
                 );
 
+                ui.ctx().accesskit_node_builder(res.id, |b| b.set_name( format!("{}, citation", line_label(i + 1, line)) ));
+
                 if res.changed() {
                     line.citation = normalize_ops(&line.citation);
                     self.updated = true;
@@ -419,37 +1056,61 @@ impl ProofUi {
 
                 if res.has_focus() {
                     self.current = Some(i);
+                    focused_row = Some( Rect::from_two_pos(pos2(x, y), pos2(citation_x_end, y + h)) );
                 }
 
                 if Some(i) == self.current && res.lost_focus() {
                     self.current = None;
                 }
             }
+        }
 
-            // Go back and draw nested subproof lines where needed.
-            if line.depth > 0 {
-                let y_end = y + (h + LINE_NUMBER_VERT_PAD / 2.0);
+        // Show the verdict on the line being edited just below it. Tooltips aren't
+        // drawn in this layer, so the position has to be moved into screen space.
+        if let (Some(row), Some((_, passed, message))) = (focused_row, &self.line_check) {
+            let transform = ui.ctx().memory(|m| m.layer_transforms.get(&ui.layer_id()).copied() ).unwrap_or_default();
 
-                let r = match line.premise {
-                    false => 1..=line.depth,
-                    true => 1..=(line.depth - 1)
-                };
+            let text = match passed {
+                true  => RichText::new(format!("✔ {message}")).color(Color32::GREEN),
+                false => RichText::new(format!("✖ {message}")).color(ui.visuals().error_fg_color),
+            };
 
-                for i in r {
-                    p.vline(
-                        x + (SUBPROOF_INDENTATION * i as f32) - SUBPROOF_LINE_PAD,
-                        y - (LINE_NUMBER_VERT_PAD / 2.0)..=y_end,
-                        Stroke::new(1.0, text_color)
-                    );
-                }
-            }
+            show_tooltip_at(ui.ctx(), ui.id().with("line_check"), Some(transform * row.left_bottom()), |ui| {
+                ui.label(text);
+            });
+        }
 
-            y += h + LINE_NUMBER_VERT_PAD;
+        // Draw the scope and premise lines for the proof and each of its subproofs.
+        let bar_x  = |depth: u16| x + SUBPROOF_INDENTATION * depth as f32 - SUBPROOF_LINE_PAD;
+        let stroke = Stroke::new(1.0, text_color);
+
+        for bar in bars(self.visible()) {
+            let y = bar.y_span(&rows);
+
+            match bar {
+                Bar::Scope { depth, .. } => p.vline(
+                    bar_x(depth),
+                    y,
+                    stroke
+                ),
+                Bar::Assumptions { depth, .. } => p.hline(
+                    bar_x(depth)..=(citation_x_start - SENTENCE_CITATION_PAD),
+                    *y.start(),
+                    stroke
+                ),
+            };
         }
 
-        let mut y = 0.0 + (h + LINE_NUMBER_VERT_PAD);
+        let mut moved = None;
+
+        for i in 0..shown {
+            if replaying {
+                break;
+            }
+
+            let y = rows.top(i);
+            let row_h = rows.height(i);
 
-        for i in 0..self.lines.len() {
             let hover_zone = Rect::from_two_pos(pos2(0.0, y), pos2(linectl_x_end, y + 90.0));
 
             let linectl_r = Rect::from_two_pos(
@@ -463,6 +1124,28 @@ impl ProofUi {
                 break;
             }
     
+            // If a line is being dragged, mark where it would land and handle the drop.
+            if let Some(MoveLine(src)) = DragAndDrop::payload::<MoveLine>(ui.ctx()).as_deref() {
+                let drop_zone = Rect::from_two_pos(
+                    pos2(0.0, y),
+                    pos2(linectl_x_end, y + row_h + LINE_NUMBER_VERT_PAD)
+                );
+
+                let pointer = ui.ctx().input(|i| i.pointer.interact_pos() );
+
+                if pointer.is_some_and(|pointer| drop_zone.contains(self.transform.inverse() * pointer)) {
+                    p.hline(
+                        x..=linectl_x_start,
+                        y + row_h + LINE_NUMBER_VERT_PAD / 2.0,
+                        Stroke::new(2.0, ui.visuals().selection.stroke.color)
+                    );
+
+                    if ui.ctx().input(|i| i.pointer.any_released() ) {
+                        moved = Some((*src, i));
+                    }
+                }
+            }
+    
             if let Some(pointer) = ui.ctx().input(|i| i.pointer.hover_pos() ) {
                 if hover_zone.contains(self.transform.inverse() * pointer) {
                     ui.put(
@@ -478,22 +1161,512 @@ impl ProofUi {
                     );
                 }
             }
+        }
 
-            y += h + LINE_NUMBER_VERT_PAD;
+        if let Some((src, dst)) = moved {
+            self.move_block(src, dst);
         }
 
+        let y = rows.end(header);
+
+        self.draw_minimap(ui, &rows, y);
+
         if self.transform.translation.y < -y + 100.0 {
             self.transform.translation.y = -y + 100.0;
         }
     }
+
+    /// Scroll so that `y` (in the proof's coordinates) is in the middle of the `view`, as far as possible.
+    fn center_on(&mut self, view: Rect, y: f32) {
+        self.transform.translation.y = (view.height() / 2.0 - y).min(0.0);
+    }
+
+    /// Select the first line that failed the last check, and scroll to it.
+    /// 
+    /// Returns `false` if no line failed.
+    pub fn jump_to_first_error(&mut self) -> bool {
+        let Some(i) = self.status.iter().position(|s| *s == Some(false) ) else {
+            return false
+        };
+
+        self.focus_to = Some(i);
+        self.pan_to   = Some(i);
+
+        true
+    }
+
+    /// Draw a scaled-down outline of the proof's subproofs down the right edge of the view,
+    /// with the visible lines highlighted. Clicking or dragging on it scrolls the proof.
+    /// 
+    /// Only drawn when the proof (`height` tall, with lines laid out as in `rows`) doesn't fit in the view.
+    fn draw_minimap(&mut self, ui: &mut Ui, rows: &Rows, height: f32) {
+        // The visible part of the proof, in the same coordinates as the proof itself.
+        let view = ui.clip_rect();
+
+        if height <= view.height() {
+            return;
+        }
+
+        let strip = Rect::from_min_max(
+            pos2(view.right() - MINIMAP_WIDTH, view.top()),
+            view.right_bottom()
+        );
+
+        let scale  = strip.height() / height;
+        let to_map = |y: f32| strip.top() + y * scale;
+
+        let res = ui.interact(strip, ui.id().with("minimap"), Sense::click_and_drag());
+
+        if let Some(pointer) = res.interact_pointer_pos() {
+            self.center_on(view, (pointer.y - strip.top()) / scale);
+        }
+
+        let p = ui.painter_at(strip);
+        let visuals = ui.visuals();
+        let stroke = Stroke::new(1.0, visuals.strong_text_color());
+
+        p.rect_filled(strip, 0.0, visuals.extreme_bg_color);
+
+        p.rect_filled(
+            Rect::from_x_y_ranges(strip.x_range(), to_map(view.top())..=to_map(view.bottom())),
+            0.0,
+            visuals.selection.bg_fill.gamma_multiply(0.5)
+        );
+
+        for bar in bars(self.visible()) {
+            let y = bar.y_span(rows);
+
+            match bar {
+                Bar::Scope { depth, .. } => p.vline(
+                    strip.left() + MINIMAP_INDENTATION * (depth + 1) as f32,
+                    to_map(*y.start())..=to_map(*y.end()),
+                    stroke
+                ),
+                Bar::Assumptions { depth, .. } => p.hline(
+                    strip.left() + MINIMAP_INDENTATION * (depth + 1) as f32..=(strip.right() - MINIMAP_INDENTATION),
+                    to_map(*y.start()),
+                    stroke
+                ),
+            };
+        }
+    }
+
+    /// Find the lines whose citations contain `*` wildcards, along with the rule they cite and
+    /// their (possibly wild) citations. Lines whose other citations are malformed are skipped.
+    fn wildcards(&self) -> Vec<(usize, String, Vec<Option<LineNumber>>)> {
+        self.lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, l)| {
+                let mut tokens = l.citation
+                    .split(|c: char| c == ',' || c == ';' || c.is_whitespace() )
+                    .filter(|t| !t.is_empty() );
+
+                let rule = Citation::parse( tokens.next()? ).ok()?.r;
+
+                let cited: Vec<_> = tokens
+                    .map(|t| match t {
+                        "*" => Some(None),
+                        t   => LineNumber::parse(t).ok().map(Some)
+                    })
+                    .collect::<Option<_>>()?;
+
+                cited
+                    .iter()
+                    .any(Option::is_none)
+                    .then_some( (i, rule, cited) )
+            })
+            .collect()
+    }
+
+    /// Copy the subproof opened on line `n` to the clipboard, renumbered to start from 1.
+    fn copy_subproof(&mut self, n: usize, ctx: &Context) {
+        let p: Vec<_> = self
+            .lines
+            .iter()
+            .map(|l| (l.depth, l.sentence.as_str(), l.citation.as_str()) )
+            .collect();
+
+        let Some(s) = Proof::parse_with(p, self.alphabet).ok().and_then(|p| p.extract_subproof(n as u16 + 1) ) else {
+            self.output.clear();
+            self.output.push("Fix the errors in your proof before copying from it.".to_string());
+            return;
+        };
+
+        super::clipboard::copy(ctx, s.to_carnap());
+
+        self.output.clear();
+        self.output.push(
+            format!("Copied lines {}-{}. Citations of lines outside the subproof were replaced with ?.", n + 1, self.block_end(n) + 1)
+        );
+    }
+
+    /// Copy the whole proof to the clipboard, in the format produced by `render`.
+    pub fn copy_proof(&mut self, ctx: &Context, render: impl FnOnce(&Proof) -> String) {
+        let p: Vec<_> = self
+            .lines
+            .iter()
+            .map(|l| (l.depth, l.sentence.as_str(), l.citation.as_str()) )
+            .collect();
+
+        let Ok(p) = Proof::parse_with(p, self.alphabet) else {
+            self.output.clear();
+            self.output.push("Fix the errors in your proof before exporting it.".to_string());
+            return;
+        };
+
+        super::clipboard::copy(ctx, render(&p));
+    }
+
+    /// Describe the proof for a [share link](super::share).
+    /// 
+    /// Fails (with an explanation) if the link couldn't reproduce the proof.
+    fn shared(&self) -> Result<super::share::Shared, &'static str> {
+        if self.custom {
+            return Err("Proofs using a custom ruleset can't be shared as links.");
+        }
+
+        let p: Vec<_> = self
+            .lines
+            .iter()
+            .map(|l| (l.depth, l.sentence.as_str(), l.citation.as_str()) )
+            .collect();
+
+        let Ok(p) = Proof::parse_with(p, self.alphabet) else {
+            return Err("Fix the errors in your proof before sharing it.");
+        };
+
+        let lines = p.to_compact();
+
+        // Links are always read back with the default alphabet.
+        if Proof::parse_compact(&lines).is_err() {
+            return Err("Only proofs using uppercase sentence letters can be shared as links.");
+        }
+
+        Ok(super::share::Shared {
+            lines,
+            conclusions: self.conclusions.clone(),
+            rules: self.rules,
+            require_all: self.require_all,
+        })
+    }
+
+    /// Copy a link that opens this proof to the clipboard.
+    pub fn share_link(&mut self, ctx: &Context) {
+        match self.shared() {
+            Ok(shared) => super::clipboard::copy(ctx, super::share::publish(&shared)),
+            Err(e)     => {
+                self.output.clear();
+                self.output.push( e.to_string() );
+            }
+        }
+    }
+
+    /// Open a proof from a share link, if it's well formed.
+    pub fn from_shared(shared: super::share::Shared) -> Option<Self> {
+        let p = Proof::parse_compact(&shared.lines).ok()?;
+
+        for conclusion in &shared.conclusions {
+            Sentence::is_wff(conclusion).ok()?;
+        }
+
+        let lines = p.lines
+            .iter()
+            .map(|l| LineUi {
+                premise  : l.is_premise(),
+                depth    : l.d,
+                sentence : l.s.to_string(),
+                citation : l.c.to_string(),
+            })
+            .collect();
+
+        // Tick everything the checker will enable anyway, so that editing the argument shows the same rulesets.
+        let mut rules = [false; rulesets::ALL_RULESETS.len()];
+
+        for (i, _) in shared.rules.iter().enumerate().filter(|(_, r)| **r) {
+            rulesets::enable_ruleset(&mut rules, i);
+        }
+
+        Some(Self {
+            premises: p.premises().map(Sentence::to_string).collect(),
+            conclusions: shared.conclusions,
+            require_all: shared.require_all,
+            checker: Checker::from_rulesets(&rules),
+            rules,
+            lines,
+            updated: true,
+            ..Default::default()
+        })
+    }
+
+    /// Check the line being edited on its own, for [`line_check`](ProofUi::line_check).
+    /// 
+    /// Like the rest of the proof, this waits for the Check button once the proof is too large for live checking.
+    fn check_current(&mut self) {
+        let Some(i) = self.current else {
+            self.line_check = None;
+            return
+        };
+
+        if !self.updated && self.line_check.as_ref().is_some_and(|(j, ..)| *j == i) {
+            return
+        }
+
+        if self.lines.len() > LIVE_CHECK_LINE_LIMIT && !self.check_now {
+            self.line_check = None;
+            return
+        }
+
+        let n = i as u16 + 1;
+
+        let p: Vec<_> = self
+            .lines
+            .iter()
+            .map(|l| (l.depth, l.sentence.as_str(), l.citation.as_str()) )
+            .collect();
+
+        let verdict = match Proof::parse_with(p, self.alphabet) {
+            Ok(p) => self.checker.explain_line(&p, n).map(|r| match r {
+                LineReport { error: Some(e), .. } => (false, e.to_string()),
+                LineReport { rule, .. }           => (true, format!("{rule} is used correctly")),
+            }),
+            Err(e) => Some(match e.into_iter().find(|(l, _)| *l == n) {
+                Some((_, e)) => (false, e.to_string()),
+                None         => (false, "fix the lines that don't parse to check this one".to_string()),
+            })
+        };
+
+        self.line_check = verdict.map(|(passed, message)| (i, passed, message) );
+    }
+
+    /// Draw the buttons and slider that step through a replay.
+    fn replay_controls(&mut self, ui: &mut Ui) {
+        let Some(n) = self.replay else {
+            return
+        };
+
+        let mut step = n;
+
+        if ui.button("Prev").clicked() {
+            step = step.saturating_sub(1).max(1);
+        }
+
+        ui.add(
+            Slider::new(&mut step, 1..=self.lines.len()).text("lines shown")
+        );
+
+        if ui.button("Next").clicked() {
+            step = (step + 1).min(self.lines.len());
+        }
+
+        if ui.button("Stop Replay").clicked() {
+            self.set_replay(false);
+            return;
+        }
+
+        if step != n {
+            self.replay  = Some(step);
+            self.updated = true;
+        }
+    }
+
+    /// Explain the justification of each line revealed so far in a replay.
+    fn narrate(&mut self, p: &Proof) {
+        let n = self.visible().len();
+
+        self.output.clear();
+
+        for report in self.checker.check_proof_verbose(p).into_iter().take(n) {
+            let rule = &report.rule;
+            let desc = rulesets::describe_rule(rule).unwrap_or_else(|| String::from("Not a known rule.") );
+
+            self.output.push(match report.error {
+                None    => format!("line {}: {rule} - {desc}", report.line),
+                Some(e) => format!("line {}: {rule} - {desc} (but this line is wrong: {e})", report.line),
+            });
+        }
+    }
+
+    /// Rewrite every line's sentence and citation in canonical form, with Unicode
+    /// operators and consistent spacing. Lines that don't parse are left as they are, and reported.
+    fn normalize(&mut self) {
+        self.output.clear();
+
+        for (i, line) in self.lines.iter_mut().enumerate() {
+            match Sentence::parse_with(&line.sentence, self.alphabet) {
+                Ok(s)  => line.sentence = s.to_string(),
+                Err(e) => self.output.push( format!("line {}: {e}", i + 1) )
+            }
+
+            if line.premise && line.citation.is_empty() {
+                continue;
+            }
+
+            match Citation::parse(&line.citation) {
+                Ok(c)  => line.citation = c.to_string(),
+                Err(e) => self.output.push( format!("line {}: {e}", i + 1) )
+            }
+        }
+
+        match self.output.is_empty() {
+            true  => self.updated = true,
+            false => self.output.insert(0, "Normalized what could be parsed; these lines were left alone:".to_string())
+        }
+    }
+
+    /// Remove every line with neither a sentence nor a citation, and report the
+    /// lines that still have a sentence but no citation.
+    /// 
+    /// Premises and assumptions are never removed, as they shape the proof. Citations
+    /// are renumbered to follow the lines that remain, as when moving a block.
+    fn clean_up(&mut self) {
+        let before = self.lines.len();
+
+        let keep: Vec<_> = self.lines
+            .iter()
+            .map(|l| l.premise || !l.sentence.trim().is_empty() || !l.citation.trim().is_empty() )
+            .collect();
+
+        // Map old line numbers (1-indexed) to new ones.
+        let mut renumber = vec![0_u16; before + 1];
+        let mut n = 0;
+
+        for (i, _) in keep.iter().enumerate().filter(|(_, k)| **k) {
+            n += 1;
+            renumber[i + 1] = n;
+        }
+
+        let mut keep = keep.into_iter();
+        self.lines.retain(|_| keep.next() == Some(true) );
+
+        self.renumber_citations(&renumber);
+
+        // Removing every line of a theorem would leave nothing to work from.
+        if self.lines.is_empty() {
+            self.lines.push(
+                LineUi::new(false, 0)
+            );
+        }
+
+        let removed = before.saturating_sub( self.lines.len() );
+
+        self.notes.clear();
+        self.notes.push(match removed {
+            0 => "No empty lines to remove.".to_string(),
+            1 => "Removed 1 empty line.".to_string(),
+            n => format!("Removed {n} empty lines."),
+        });
+
+        for (i, line) in self.lines.iter().enumerate() {
+            if !line.premise && !line.sentence.trim().is_empty() && line.citation.trim().is_empty() {
+                self.notes.push( format!("line {}: missing a citation", i + 1) );
+            }
+        }
+
+        if removed > 0 {
+            self.current = None;
+        }
+
+        self.updated = true;
+    }
+
+    /// Describe the premises and assumptions in force at line `n`,
+    /// e.g. "In scope: premise A (line 1), assumption ¬B (line 3)".
+    fn scope_summary(&self, n: usize) -> String {
+        let items: Vec<_> = in_scope(&self.lines, n)
+            .into_iter()
+            .map(|i| {
+                let line = &self.lines[i];
+                let kind = if line.depth == 0 { "premise" } else { "assumption" };
+
+                format!("{kind} {} (line {})", line.sentence.trim(), i + 1)
+            })
+            .collect();
+
+        match items.is_empty() {
+            true  => "In scope: nothing (no premises or assumptions)".to_string(),
+            false => format!("In scope: {}", items.join(", "))
+        }
+    }
+
+    /// Report which of the required conclusions have been reached, e.g. "Conclusions: B ✔, C ✖".
+    fn checklist(&mut self, reached: &[bool]) {
+        let items: Vec<_> = self
+            .conclusions
+            .iter()
+            .zip(reached)
+            .map(|(c, r)| format!("{c} {}", if *r { "✔" } else { "✖" }))
+            .collect();
+
+        self.output.push( format!("Conclusions: {}", items.join(", ")) );
+    }
+
+    /// Look for a line that would reach one of the conclusions in a single step,
+    /// and offer it as [`hint`](ProofUi::hint).
+    fn find_hint(&mut self) {
+        self.hint = None;
+        self.output.clear();
+
+        let p: Vec<_> = self
+            .lines
+            .iter()
+            .map(|l| (l.depth, l.sentence.as_str(), l.citation.as_str()) )
+            .collect();
+
+        let Some(p) = Proof::parse_with(p, self.alphabet).ok().filter(|p| self.checker.check_proof(p).is_ok() ) else {
+            self.output.push("Fix the errors in your proof before asking for a hint.".to_string());
+            return;
+        };
+
+        if self.conclusions.is_empty() {
+            self.output.push("This proof has no fixed goal to find a step towards.".to_string());
+            return;
+        }
+
+        let found = self.conclusions.iter().find_map(|c| {
+            let s = Sentence::parse_with(c, self.alphabet).expect("Conclusions should have been validated on proof creation");
+
+            // Conclusions that have already been reached don't need a hint.
+            if p.reaches(&s) {
+                return None
+            }
+
+            self.checker
+                .one_step_to(&p, &s)
+                .map(|(rule, cited)| (c, rule, cited))
+        });
+
+        let Some((conclusion, rule, cited)) = found else {
+            self.output.push("No single step reaches the conclusion from here.".to_string());
+            return;
+        };
+
+        let cited: Vec<_> = cited.iter().map(LineNumber::to_string).collect();
+
+        let line = LineUi {
+            premise  : false,
+            depth    : 0,
+            sentence : conclusion.clone(),
+            citation : format!("{rule} {}", cited.join(", ")),
+        };
+
+        self.output.push(
+            format!("Hint: {} follows from {} in one step.", line.sentence, line.citation)
+        );
+
+        self.hint = Some(line);
+    }
 }
 
 impl Widget for &mut ProofUi {
     fn ui(self, ui: &mut Ui) -> Response {
-        let (w, h) = super::window_size(ui);
+        let h = super::window_height(ui);
+
+        // Everything below is laid out against this one reading of the window's width.
+        let widths = Widths::new(self.window, self.wide);
 
         let (id, rect) = ui.allocate_space(
-            Vec2::new(w * 0.70, h * 0.80)
+            Vec2::new(widths.proof, h * 0.80)
         );
 
         let transform = &mut self.transform;
@@ -518,7 +1691,7 @@ impl Widget for &mut ProofUi {
             .show(ui.ctx(), |ui| {
                 ui.set_clip_rect(transform.inverse() * rect);
                 ui.style_mut().wrap = Some(false);
-                self.draw(ui)
+                self.draw(ui, widths.proof)
             })
             .response
             .layer_id;
@@ -527,43 +1700,191 @@ impl Widget for &mut ProofUi {
         
         ui.separator();
 
+        let deferred = self.lines.len() > LIVE_CHECK_LINE_LIMIT;
+
+        ui.horizontal(|ui| {
+            if self.replay.is_some() {
+                self.replay_controls(ui);
+                return;
+            }
+
+            if deferred && ui
+                .button("Check")
+                .on_hover_text("Run the proof checker")
+                .clicked()
+            {
+                self.updated = true;
+                self.check_now = true;
+            }
+
+            if ui
+                .checkbox(&mut self.explore, "Explore")
+                .on_hover_text("Write * in place of a line number to find the lines that fit. The proof won't be marked as correct while any remain.")
+                .changed()
+            {
+                self.updated = true;
+            }
+
+            if ui
+                .button("Normalize")
+                .on_hover_text("Rewrite every line with standard symbols and spacing")
+                .clicked()
+            {
+                self.normalize();
+            }
+
+            if ui
+                .button("Clean Up")
+                .on_hover_text("Remove empty lines, and list any lines still missing a citation")
+                .clicked()
+            {
+                self.clean_up();
+            }
+
+            if ui
+                .button("Hint")
+                .on_hover_text("Look for a single step that reaches the conclusion")
+                .clicked()
+            {
+                self.find_hint();
+            }
+
+            let complete = self.hint.as_ref().map(|l| format!("Complete with {}", l.citation) );
+
+            if let Some(label) = complete {
+                if ui.button(label).clicked() {
+                    self.lines.extend( self.hint.take() );
+                    self.updated = true;
+                }
+            }
+        });
+
+        // Show what the line being edited may rely on, to make scoping (and discharging) concrete.
+        if let Some(i) = self.current.filter(|i| *i < self.lines.len() ) {
+            ui.label( RichText::new( self.scope_summary(i) ).italics() );
+        }
+
+        self.check_current();
+
         ui.centered_and_justified( |ui| {
+            if self.updated && deferred && !self.check_now && self.replay.is_none() {
+                self.output.clear();
+                self.output.push(
+                    format!("This proof is too large for live checking ({} lines) - press Check to check it.", self.lines.len())
+                );
+
+                self.status.clear();
+
+                self.updated = false;
+            }
+
             if self.updated {
+                self.hint = None;
+
+                let wildcards = match self.explore && self.replay.is_none() {
+                    true  => self.wildcards(),
+                    false => vec![]
+                };
+
+                // Lines with wildcards are checked as placeholders, then resolved separately.
                 let p: Vec<_> = self
                     .lines
                     .iter()
-                    .map(|l| {
-                        (l.depth, l.sentence.as_str(), l.citation.as_str())
+                    .enumerate()
+                    .map(|(i, l)| {
+                        let citation = match wildcards.iter().any(|(w, ..)| *w == i) {
+                            true  => "?",
+                            false => l.citation.as_str()
+                        };
+
+                        (l.depth, l.sentence.as_str(), citation)
                     })
                     .collect();
 
-                match Proof::parse(p) {
+                self.status.clear();
+
+                match Proof::parse_with(p, self.alphabet) {
+                    Ok(p) if self.replay.is_some() => self.narrate(&p),
                     Ok(p) => {
-                        if let Err(e) = self.checker.check_proof(&p) {
-                            self.output.clear();
-                            self.output.push("Invalid proof!".to_string());
-
-                            for (line, err) in e {
-                                self.output.push(
-                                    format!("line {line}: {err}")
-                                )
-                            }
-                        }
-                        else {
-                            self.output.clear();
-                            if p.reached_conclusion(&self.conclusion) {
-                                if p.contains_placeholders() {
-                                    self.output.push("You've reached the conclusion, but your proof still contains placeholder citations.".to_string());
-                                } else {
-                                    self.output.push("This proof is correct!".to_string());
+                        let reports = self.checker.check_proof_verbose(&p);
+
+                        self.status = line_status(&self.lines, &reports);
+
+                        let conclusions: Vec<_> = self
+                            .conclusions
+                            .iter()
+                            .map(|c| Sentence::parse_with(c, self.alphabet).expect("Conclusions should have been validated on proof creation") )
+                            .collect();
+
+                        self.output.clear();
+
+                        // Reuse the reports from the gutter, rather than checking the proof a second time.
+                        let each: Vec<_> = conclusions.iter().map(|c| p.reaches(c) ).collect();
+
+                        let goal = (!each.is_empty()).then(|| match self.require_all {
+                            true  => each.iter().all(|r| *r),
+                            false => each.iter().any(|r| *r)
+                        });
+
+                        let outcome = ProofOutcome::from_reports(&p, reports, goal);
+                        let reached = self.require_all.then_some(each);
+
+                        // Which conclusions have been reached only matters if the proof is otherwise fine.
+                        let reached = reached.filter(|_| !matches!(outcome, ProofOutcome::Invalid(_)) );
+
+                        match outcome {
+                            ProofOutcome::Invalid(e) => {
+                                self.output.push("Invalid proof!".to_string());
+
+                                for (line, err) in e {
+                                    self.output.push(
+                                        format!("line {line}: {err}")
+                                    )
                                 }
-                            } 
-                            else {
+                            }
+                            ProofOutcome::Incomplete => {
+                                self.output.push("You've reached the conclusion, but your proof still contains placeholder citations.".to_string());
+                            }
+                            ProofOutcome::Complete => {
+                                self.output.push("This proof is correct!".to_string());
+                            }
+                            ProofOutcome::Unclosed(d) => {
+                                self.output.push(format!("No errors, but your proof ends inside a subproof (depth {d}). Discharge it to return to the main proof."));
+                            }
+                            ProofOutcome::Valid if self.conclusions.is_empty() => {
+                                self.output.push("No errors.".to_string());
+                            }
+                            ProofOutcome::Valid if self.require_all => {
+                                self.output.push("No errors, but you haven't reached every conclusion.".to_string());
+                            }
+                            ProofOutcome::Valid => {
                                 self.output.push("No errors, but you haven't reached the conclusion.".to_string());
                             }
                         }
+
+                        if let Some(reached) = reached {
+                            self.checklist(&reached);
+                        }
+
+                        for (i, rule, cited) in wildcards {
+                            let n = i + 1;
+
+                            let found = self.checker
+                                .resolve_wildcards(&p, n as u16, &rule, &cited)
+                                .map(|l| l.iter().map(LineNumber::to_string).collect::<Vec<_>>().join(", ") );
+
+                            self.output.push(match found {
+                                Some(l) => format!("line {n}: {} fits as {rule} {l}", self.lines[i].citation),
+                                None    => format!("line {n}: nothing fits {}", self.lines[i].citation)
+                            });
+                        }
                     }
                     Err(e) => {
+                        // Lines that parsed can't be checked until the rest do.
+                        self.status = (1..=self.lines.len() as u16)
+                            .map(|n| e.iter().any(|(l, _)| *l == n).then_some(false) )
+                            .collect();
+
                         self.output.clear();
                         self.output.push("Failed to parse proof!".to_string());
 
@@ -576,13 +1897,17 @@ impl Widget for &mut ProofUi {
                 }
 
                 self.updated = false;
+                self.check_now = false;
             }
 
+            // Anything reported alongside an edit goes below the results of checking it.
+            self.output.append(&mut self.notes);
+
             Frame::group(ui.style())
                 .stroke(Stroke::new(1.0, ui.visuals().strong_text_color()))
                 .show(ui, |ui| {
                     ScrollArea::vertical()
-                        .max_width(w * 0.75)
+                        .max_width(widths.output)
                         .auto_shrink([false, false])
                         .show(ui, |ui| {
                             ui.vertical(|ui| {
@@ -611,4 +1936,587 @@ impl Widget for &mut ProofUi {
 
         super::dummy_response(ui)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(shape: &[(bool, u16)]) -> Vec<LineUi> {
+        shape
+            .iter()
+            .map(|(premise, depth)| LineUi::new(*premise, *depth))
+            .collect()
+    }
+
+    fn line(premise: bool, depth: u16, sentence: &str, citation: &str) -> LineUi {
+        LineUi {
+            premise,
+            depth,
+            sentence : sentence.to_string(),
+            citation : citation.to_string(),
+        }
+    }
+
+    #[test]
+    fn block_ends() {
+        let l = lines(&[
+            (true, 0),
+            (true, 1),
+            (false, 1),
+            (true, 2),
+            (false, 2),
+            (false, 1),
+            (true, 1),
+            (false, 0),
+        ]);
+
+        // A subproof's block runs until its sibling (or its parent) picks back up...
+        assert_eq!( block_end(&l, 1), 5 );
+        assert_eq!( block_end(&l, 3), 4 );
+        assert_eq!( block_end(&l, 6), 6 );
+        // ...while any other line, and the premises of the proof, stand alone.
+        assert_eq!( block_end(&l, 0), 0 );
+        assert_eq!( block_end(&l, 2), 2 );
+        assert_eq!( block_end(&l, 7), 7 );
+    }
+
+    #[test]
+    fn move_block() {
+        let proof = || ProofUi {
+            lines: vec![
+                line(true, 0, "A", "PR"),
+                line(true, 0, "B", "PR"),
+                line(true, 1, "C", "AS"),
+                line(false, 1, "A ∧ C", "∧I 1, 3"),
+                line(false, 0, "C → (A ∧ C)", "→I 3-4"),
+                line(false, 0, "B ∧ A", "∧I 2, 1"),
+            ],
+            ..Default::default()
+        };
+
+        let shape = |ui: &ProofUi| ui.lines
+            .iter()
+            .map(|l| (l.depth, l.sentence.clone(), l.citation.clone()) )
+            .collect::<Vec<_>>();
+
+        let row = |d: u16, s: &str, c: &str| (d, s.to_string(), c.to_string());
+
+        // Moving a line up, past a whole subproof.
+        let mut ui = proof();
+        ui.move_block(5, 1);
+
+        assert_eq!(shape(&ui), vec![
+            row(0, "A", "PR"),
+            row(0, "B", "PR"),
+            row(0, "B ∧ A", "∧I 2, 1"),
+            row(1, "C", "AS"),
+            row(1, "A ∧ C", "∧I 1, 4"),
+            row(0, "C → (A ∧ C)", "→I 4-5"),
+        ]);
+        assert!( ui.updated );
+
+        // Moving a subproof down, past the lines after it, as a unit.
+        let mut ui = proof();
+        ui.move_block(2, 5);
+
+        assert_eq!(shape(&ui), vec![
+            row(0, "A", "PR"),
+            row(0, "B", "PR"),
+            row(0, "C → (A ∧ C)", "→I 5-6"),
+            row(0, "B ∧ A", "∧I 2, 1"),
+            row(1, "C", "AS"),
+            row(1, "A ∧ C", "∧I 1, 5"),
+        ]);
+
+        // A line dropped into a subproof takes on its depth.
+        let mut ui = proof();
+        ui.move_block(5, 2);
+
+        assert_eq!( ui.lines[3].depth, 1 );
+        assert_eq!( ui.lines[3].citation, "∧I 2, 1" );
+        assert_eq!( ui.lines[4].citation, "∧I 1, 3" );
+        assert_eq!( ui.lines[5].citation, "→I 3-5" );
+
+        // The premises of the proof stay put, and nothing can be dropped among them.
+        for (src, dst) in [(0, 3), (5, 0), (2, 1)] {
+            let mut ui = proof();
+            ui.move_block(src, dst);
+
+            assert_eq!( shape(&ui), shape(&proof()) );
+            assert!( !ui.updated );
+        }
+    }
+
+    #[test]
+    fn line_labels() {
+        let shape = lines(&[(true, 0), (false, 0), (true, 1), (false, 2)]);
+
+        assert_eq!(line_label(1, &shape[0]), "Line 1, premise");
+        assert_eq!(line_label(2, &shape[1]), "Line 2");
+        assert_eq!(line_label(3, &shape[2]), "Line 3, opens assumption level 1");
+        assert_eq!(line_label(4, &shape[3]), "Line 4, assumption level 2");
+    }
+
+    #[test]
+    fn bars() {
+        // 1 | A        PR
+        // 2 | B        PR
+        //   |----
+        // 3 | | C      AS
+        //   | |----
+        // 4 | | | D    AS
+        //   | | |----
+        // 5 | | | D    R 4
+        // 6 | | E      ...
+        // 7 | F        ...
+        let l = lines(&[
+            (true, 0),
+            (true, 0),
+            (true, 1),
+            (true, 2),
+            (false, 2),
+            (false, 1),
+            (false, 0),
+        ]);
+
+        assert_eq!(
+            super::bars(&l),
+            vec![
+                Bar::Scope { depth: 0, start: 0, end: 6 },
+                Bar::Assumptions { depth: 0, line: 1 },
+                Bar::Scope { depth: 1, start: 2, end: 5 },
+                Bar::Assumptions { depth: 1, line: 2 },
+                Bar::Scope { depth: 2, start: 3, end: 4 },
+                Bar::Assumptions { depth: 2, line: 3 },
+            ]
+        );
+
+        // Theorems have no premises to underline.
+        assert_eq!(
+            super::bars(&lines(&[(true, 1), (false, 0)])),
+            vec![
+                Bar::Scope { depth: 0, start: 0, end: 1 },
+                Bar::Scope { depth: 1, start: 0, end: 0 },
+                Bar::Assumptions { depth: 1, line: 0 },
+            ]
+        );
+
+        assert!( super::bars(&[]).is_empty() );
+    }
+
+    #[test]
+    fn sibling_bars() {
+        // 1 | A        PR
+        //   |----
+        // 2 | | B      AS
+        //   | |----
+        // 3 | | A      R 1
+        //   |
+        // 4 | | C      AS
+        //   | |----
+        // 5 | | A      R 1
+        // 6 | A        R 1
+        let l = lines(&[
+            (true, 0),
+            (true, 1),
+            (false, 1),
+            (true, 1),
+            (false, 1),
+            (false, 0),
+        ]);
+
+        let bars = super::bars(&l);
+
+        assert_eq!(
+            bars,
+            vec![
+                Bar::Scope { depth: 0, start: 0, end: 5 },
+                Bar::Assumptions { depth: 0, line: 0 },
+                Bar::Scope { depth: 1, start: 1, end: 2 },
+                Bar::Assumptions { depth: 1, line: 1 },
+                Bar::Scope { depth: 1, start: 3, end: 4 },
+                Bar::Assumptions { depth: 1, line: 3 },
+            ]
+        );
+
+        let h = 20.0;
+        let rows = Rows::new(h, vec![h; 6]);
+        let span = |i: usize| bars[i].y_span(&rows);
+
+        // Both siblings sit inside the outer scope...
+        assert!( span(0).start() <= span(2).start() && span(4).end() <= span(0).end() );
+        // ...with a gap between them, rather than running together or overlapping.
+        assert_eq!( span(4).start() - span(2).end(), LINE_NUMBER_VERT_PAD / 2.0 );
+        // Each is underlined where its bar would stop if it were a single line.
+        assert_eq!( *span(3).start(), span(2).start() + h + LINE_NUMBER_VERT_PAD / 2.0 );
+        assert_eq!( span(5).start(), span(5).end() );
+    }
+
+    #[test]
+    fn instructions() {
+        use Instruction as I;
+
+        let s = |i: &[&str]| -> Vec<String> { i.iter().map(|s| s.to_string() ).collect() };
+        let sentence = |s: &str| I::Sentence( s.to_string() );
+
+        assert_eq!(
+            super::instructions(&s(&["A → B", "A"]), &s(&["B"]), false),
+            vec![
+                I::Text("Construct a proof for the argument "),
+                sentence("A → B"),
+                I::Text(", "),
+                sentence("A"),
+                I::Text(" "),
+                I::Therefore,
+                I::Text(" "),
+                sentence("B"),
+            ]
+        );
+
+        assert_eq!(
+            super::instructions(&[], &s(&["A ∨ ¬A", "¬¬A → A"]), false),
+            vec![
+                I::Text("Construct a proof for any of the theorems "),
+                I::Therefore,
+                I::Text(" "),
+                sentence("A ∨ ¬A"),
+                I::Text("; "),
+                sentence("¬¬A → A"),
+            ]
+        );
+
+        assert_eq!(
+            super::instructions(&s(&["A"]), &s(&["B", "C"]), false)[4..],
+            [I::Text(" one of "), sentence("B"), I::Text("; "), sentence("C")]
+        );
+
+        assert_eq!(
+            super::instructions(&s(&["A"]), &s(&["B", "C"]), true)[4..],
+            [I::Text(" all of "), sentence("B"), I::Text("; "), sentence("C")]
+        );
+
+        assert_eq!(
+            super::instructions(&[], &[], true),
+            vec![I::Text("Construct a proof (no fixed goal)")]
+        );
+    }
+
+    #[test]
+    fn change_rules() {
+        use crate::check::CheckError;
+
+        let mut rules = [false; rulesets::ALL_RULESETS.len()];
+        rules[..2].fill(true);
+
+        let mut ui = ProofUi {
+            lines: vec![
+                line(true, 0, "A ∨ B", "PR"),
+                line(true, 0, "¬A", "PR"),
+                line(false, 0, "B", "DS 1, 2"),
+                line(false, 0, "B ∧ B", "∧I 3, 3"),
+            ],
+            checker: Checker::from_rulesets(&rules),
+            rules,
+            ..Default::default()
+        };
+
+        let check = |ui: &ProofUi| {
+            let p: Vec<_> = ui.lines.iter().map(|l| (0, l.sentence.as_str(), l.citation.as_str()) ).collect();
+            ui.checker.check_proof( &Proof::parse(p).unwrap() )
+        };
+
+        assert_eq!( check(&ui), Ok(()) );
+
+        // Without Derived TFL, only the DS line is flagged - and nothing is lost.
+        rules[1] = false;
+        ui.change_rules(rules, false, Checker::from_rulesets(&rules));
+
+        assert!( ui.updated );
+        assert_eq!( ui.lines.len(), 4 );
+        assert_eq!( ui.rules, rules );
+        assert_eq!(
+            check(&ui),
+            Err( vec![(3, CheckError::RuleNotEnabled { rule: String::from("DS"), ruleset: "Derived TFL" })] )
+        );
+    }
+
+    #[test]
+    fn shared() {
+        let mut rules = [false; rulesets::ALL_RULESETS.len()];
+        rules[0] = true;
+
+        let ui = ProofUi {
+            premises: vec![String::from("A -> B")],
+            conclusions: vec![String::from("A -> (B ^ A)")],
+            lines: vec![
+                line(true, 0, "A -> B", "PR"),
+                line(true, 1, "A", "AS"),
+                line(false, 1, "B", "->E 1, 2"),
+                line(false, 1, "B ^ A", "^I 3, 2"),
+                line(false, 0, "A -> (B ^ A)", "->I 2-4"),
+            ],
+            checker: Checker::from_rulesets(&rules),
+            rules,
+            require_all: true,
+            ..Default::default()
+        };
+
+        let shared = ui.shared().unwrap();
+        let opened = ProofUi::from_shared( shared.clone() ).unwrap();
+
+        assert_eq!( opened.shared(), Ok( shared.clone() ) );
+        assert_eq!( opened.premises, vec!["A → B"] );
+        assert_eq!( opened.conclusions, ui.conclusions );
+        assert_eq!( opened.rules, rules );
+        assert!( opened.require_all );
+
+        // A link naming only S4 opens with everything S4 depends on ticked too.
+        let mut s4 = [false; rulesets::ALL_RULESETS.len()];
+        s4[4] = true;
+
+        let opened = ProofUi::from_shared( super::super::share::Shared { rules: s4, ..shared } ).unwrap();
+
+        assert_eq!( opened.rules, [true, true, true, true, true, false, false] );
+        assert_eq!(
+            opened.lines.iter().map(|l| (l.premise, l.depth) ).collect::<Vec<_>>(),
+            ui.lines.iter().map(|l| (l.premise, l.depth) ).collect::<Vec<_>>()
+        );
+        assert_eq!( opened.lines[3].citation, "∧I 3, 2" );
+
+        // Proofs that don't parse can't be shared...
+        let broken = ProofUi { lines: vec![line(false, 0, "A ->", "PR")], ..Default::default() };
+        assert!( broken.shared().is_err() );
+
+        // ...and links to them are ignored.
+        let mut bad = ui.shared().unwrap();
+        bad.lines.push_str("; 0|A ->|PR");
+        assert!( ProofUi::from_shared(bad).is_none() );
+    }
+
+    #[test]
+    fn line_status() {
+        use crate::check::CheckError;
+
+        let l = lines(&[(true, 0), (false, 0), (true, 1), (false, 1)]);
+
+        let report = |line: u16, error: Option<CheckError>| LineReport {
+            line,
+            rule: String::from("R"),
+            error,
+        };
+
+        let reports = [
+            report(1, None),
+            report(2, Some(CheckError::BadUsage)),
+            // Premises show as valid, whatever their report says.
+            report(3, Some(CheckError::LatePremise)),
+            report(4, None),
+        ];
+
+        assert_eq!(
+            super::line_status(&l, &reports),
+            vec![Some(true), Some(false), Some(true), Some(true)]
+        );
+    }
+
+    #[test]
+    fn jump_to_first_error() {
+        let mut ui = ProofUi {
+            lines: lines(&[(true, 0), (false, 0), (false, 0), (false, 0)]),
+            status: vec![Some(true), None, Some(false), Some(false)],
+            ..Default::default()
+        };
+
+        assert!( ui.jump_to_first_error() );
+        assert_eq!( (ui.focus_to, ui.pan_to), (Some(2), Some(2)) );
+
+        ui.status = vec![Some(true); 4];
+
+        assert!( !ui.jump_to_first_error() );
+    }
+
+    #[test]
+    fn widths() {
+        for window in [800.0, 1024.0, 1920.0] {
+            let narrow = Widths::new(window, false);
+            let wide   = Widths::new(window, true);
+
+            // Beside the rule reference, the proof and its output stay clear of it...
+            assert!( narrow.output + window * super::super::REFERENCE_FRACTION <= window );
+            // ...and without it, they can spread out - but never past the window.
+            assert!( wide.proof > narrow.proof && wide.output <= window );
+
+            assert!( narrow.proof < narrow.output && wide.proof < wide.output );
+        }
+
+        // The proof gets what's left beside the rule reference, less a margin.
+        assert_eq!( Widths::new(1024.0, false).proof, 1024.0 * 0.70 );
+    }
+
+    #[test]
+    fn clean_up() {
+        let mut ui = ProofUi {
+            lines: vec![
+                line(true, 0, "A", "PR"),
+                line(false, 0, "", ""),
+                line(true, 1, "", "AS"),
+                line(false, 1, " ", ""),
+                line(false, 1, "A", ""),
+                line(false, 0, "", "R 1"),
+                line(false, 0, "", " "),
+                line(false, 0, "", ""),
+            ],
+            ..Default::default()
+        };
+
+        ui.clean_up();
+
+        // Blank assumptions and half-written lines stay put.
+        assert_eq!(
+            ui.lines.iter().map(|l| (l.premise, l.depth, l.sentence.as_str())).collect::<Vec<_>>(),
+            vec![(true, 0, "A"), (true, 1, ""), (false, 1, "A"), (false, 0, "")]
+        );
+        assert_eq!( ui.notes, vec!["Removed 4 empty lines.", "line 3: missing a citation"] );
+        assert!( ui.updated );
+
+        ui.clean_up();
+
+        assert_eq!( ui.lines.len(), 4 );
+        assert_eq!( ui.notes[0], "No empty lines to remove." );
+
+        // A theorem always keeps a line to work from.
+        let mut ui = ProofUi { lines: vec![line(false, 0, "", "")], ..Default::default() };
+
+        ui.clean_up();
+
+        assert_eq!( ui.lines.len(), 1 );
+        assert_eq!( ui.notes, vec!["No empty lines to remove."] );
+
+        // Citations follow the lines that remain.
+        let mut ui = ProofUi {
+            lines: vec![
+                line(true, 0, "A", "PR"),
+                line(false, 0, "", ""),
+                line(true, 0, "B", "PR"),
+                line(false, 0, "A ∧ B", "∧I 1, 3"),
+                line(false, 0, "A", "R 2"),
+                line(false, 0, "B", "R 3"),
+            ],
+            ..Default::default()
+        };
+
+        ui.clean_up();
+
+        assert_eq!( ui.lines[2].citation, "∧I 1, 2" );
+        // ...and citations of removed lines become placeholders, rather than pointing at a different line.
+        assert_eq!( ui.lines[3].citation, "?" );
+        assert_eq!( ui.lines[4].citation, "R 2" );
+    }
+
+    #[test]
+    fn indentation() {
+        let mut ui = ProofUi {
+            lines: lines(&[
+                (true, 0),
+                (false, 0),
+                (true, 1),
+                (false, 1),
+                (true, 1),
+                (false, 1),
+                (false, 0),
+            ]),
+            ..Default::default()
+        };
+
+        // The premises of the proof are fixed.
+        assert!( !ui.can_indent(0) && !ui.can_outdent(0) );
+
+        // A premise can sit at most one level deeper than the line before it...
+        assert!( !ui.can_indent(2) );
+        assert!( ui.can_indent(4) );
+        // ...while any other line has to continue a subproof that's already open.
+        assert!( !ui.can_indent(1) );
+        assert!( !ui.can_indent(3) );
+
+        // Subproofs can't be outdented into the main proof...
+        assert!( !ui.can_outdent(2) );
+        // ...and other lines only if that doesn't strand the lines after them.
+        assert!( !ui.can_outdent(1) );
+        assert!( ui.can_outdent(3) );
+        assert!( ui.can_outdent(5) );
+
+        // A subproof shifts as a unit, leaving the lines after it alone.
+        ui.shift_block(4, true);
+
+        assert_eq!( ui.lines.iter().map(|l| l.depth).collect::<Vec<_>>(), vec![0, 0, 1, 1, 2, 2, 0] );
+        assert!( ui.updated );
+        assert!( ui.can_outdent(4) );
+
+        ui.shift_block(4, false);
+
+        assert_eq!( ui.lines.iter().map(|l| l.depth).collect::<Vec<_>>(), vec![0, 0, 1, 1, 1, 1, 0] );
+
+        // A lone line moves by itself.
+        ui.shift_block(3, false);
+
+        assert_eq!( ui.lines.iter().map(|l| l.depth).collect::<Vec<_>>(), vec![0, 0, 1, 0, 1, 1, 0] );
+    }
+
+    #[test]
+    fn in_scope() {
+        // 1 | A        PR
+        //   |----
+        // 2 | | B      AS
+        // 3 | | A      R 1
+        //   |
+        // 4 | | C      AS
+        //   | |----
+        // 5 | | | D    AS
+        // 6 | | | E    AS
+        //   | | |----
+        // 7 | | | A    R 1
+        // 8 | | D -> A ->I 5-7
+        let l = lines(&[
+            (true, 0),
+            (true, 1),
+            (false, 1),
+            (true, 1),
+            (true, 2),
+            (true, 2),
+            (false, 2),
+            (false, 1),
+        ]);
+
+        assert_eq!( super::in_scope(&l, 0), vec![0] );
+        assert_eq!( super::in_scope(&l, 2), vec![0, 1] );
+        // The first subproof is closed by line 4, so its assumption no longer applies...
+        assert_eq!( super::in_scope(&l, 3), vec![0, 3] );
+        // ...and both assumptions of the innermost subproof are in force.
+        assert_eq!( super::in_scope(&l, 6), vec![0, 3, 4, 5] );
+        assert_eq!( super::in_scope(&l, 7), vec![0, 3] );
+
+        // Theorems have no premises.
+        let l = lines(&[(true, 1), (false, 1), (false, 0)]);
+
+        assert_eq!( super::in_scope(&l, 1), vec![0] );
+        assert!( super::in_scope(&l, 2).is_empty() );
+    }
+
+    #[test]
+    fn wrapped_rows() {
+        let h = 20.0;
+        let rows = Rows::new(h, vec![h, 3.0 * h, h]);
+
+        assert_eq!( rows.top(0), h + LINE_NUMBER_VERT_PAD );
+        // A line wrapped onto three rows pushes everything below it down.
+        assert_eq!( rows.top(2) - rows.top(1), 3.0 * h + LINE_NUMBER_VERT_PAD );
+        assert_eq!( rows.end(h), rows.top(2) + h + LINE_NUMBER_VERT_PAD );
+
+        // Bars stretch to cover the whole of a wrapped line.
+        let bar = Bar::Scope { depth: 1, start: 1, end: 1 };
+        assert_eq!( *bar.y_span(&rows).end(), rows.top(2) - LINE_NUMBER_VERT_PAD / 2.0 );
+
+        assert_eq!( Rows::new(h, vec![]).end(h), h + LINE_NUMBER_VERT_PAD );
+    }
+}
@@ -0,0 +1,213 @@
+//! Shareable links that open a proof, by storing it in the fragment of the web app's URL.
+//!
+//! The fragment looks like `#p=...&c=...&r=...&a=...` - the proof's lines in the compact format,
+//! its conclusions (separated by semicolons), its rulesets (one `0` or `1` per ruleset) and whether
+//! every conclusion must be reached (`0` or `1`, and `0` if missing), with the first two percent-encoded.
+
+use crate::check::rulesets;
+
+/// Where the web app lives, for links shared from the native app.
+const WEB_APP_URL: &str = "https://colonial-dev.github.io/deduct/";
+
+/// A proof, as read back out of a link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shared {
+    /// The lines of the proof, in the compact format.
+    pub lines       : String,
+    pub conclusions : Vec<String>,
+    pub rules       : [bool; rulesets::ALL_RULESETS.len()],
+    /// Every conclusion must be reached, rather than any one of them.
+    pub require_all : bool,
+}
+
+/// Encode a proof as a URL fragment (without the leading `#`.)
+pub fn encode(shared: &Shared) -> String {
+    let rules: String = shared.rules
+        .iter()
+        .map(|r| if *r { '1' } else { '0' })
+        .collect();
+
+    format!(
+        "p={}&c={}&r={rules}&a={}",
+        percent_encode(&shared.lines),
+        percent_encode( &shared.conclusions.join(";") ),
+        shared.require_all as u8
+    )
+}
+
+/// Decode a URL fragment (with or without the leading `#`) made by [`encode`].
+///
+/// Returns `None` if the fragment is empty or malformed in any way.
+pub fn decode(fragment: &str) -> Option<Shared> {
+    let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+
+    let mut lines = None;
+    let mut conclusions = None;
+    let mut rules = None;
+    // Links made before this was shared leave it out.
+    let mut require_all = false;
+
+    for pair in fragment.split('&') {
+        let (key, value) = pair.split_once('=')?;
+
+        match key {
+            "p" => lines = Some( percent_decode(value)? ),
+            "c" => conclusions = Some( percent_decode(value)? ),
+            "r" => {
+                let flags: Vec<_> = value
+                    .chars()
+                    .map(|c| match c {
+                        '0' => Some(false),
+                        '1' => Some(true),
+                        _   => None
+                    })
+                    .collect::<Option<_>>()?;
+
+                rules = Some( flags.try_into().ok()? );
+            }
+            "a" => require_all = match value {
+                "0" => false,
+                "1" => true,
+                _   => return None
+            },
+            _ => return None
+        }
+    }
+
+    let conclusions: String = conclusions?;
+
+    Some(Shared {
+        lines: lines?,
+        conclusions: conclusions
+            .split(';')
+            .map(str::to_owned)
+            .filter(|c| !c.is_empty() )
+            .collect(),
+        rules: rules?,
+        require_all,
+    })
+}
+
+/// A link to the web app that opens the given proof.
+/// 
+/// On the web, the page's own address is updated to match, so it can be bookmarked.
+pub fn publish(shared: &Shared) -> String {
+    let fragment = encode(shared);
+
+    imp::set_fragment(&fragment);
+
+    format!("{}#{fragment}", imp::page_url().unwrap_or_else(|| WEB_APP_URL.to_string() ))
+}
+
+/// The proof in the page's address, if the app was opened from a link to one.
+pub fn from_page() -> Option<Shared> {
+    decode( &imp::fragment()? )
+}
+
+/// Percent-encode everything but unreserved URL characters.
+fn percent_encode(i: &str) -> String {
+    let mut out = String::with_capacity( i.len() );
+
+    for b in i.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(b as char),
+            _ => out += &format!("%{b:02X}")
+        }
+    }
+
+    out
+}
+
+/// Undo [`percent_encode`], failing on bad escapes or invalid UTF-8.
+fn percent_decode(i: &str) -> Option<String> {
+    let mut out = vec![];
+    let mut bytes = i.bytes();
+
+    while let Some(b) = bytes.next() {
+        match b {
+            b'%' => {
+                let hex = [bytes.next()?, bytes.next()?];
+                out.push( u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()? );
+            }
+            b => out.push(b)
+        }
+    }
+
+    String::from_utf8(out).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod imp {
+    pub fn page_url() -> Option<String> {
+        None
+    }
+
+    pub fn fragment() -> Option<String> {
+        None
+    }
+
+    pub fn set_fragment(_: &str) {}
+}
+
+#[cfg(target_arch = "wasm32")]
+mod imp {
+    use eframe::web_sys;
+
+    /// The address of the running web app, without any fragment.
+    pub fn page_url() -> Option<String> {
+        let location = web_sys::window()?.location();
+
+        Some( format!("{}{}", location.origin().ok()?, location.pathname().ok()?) )
+    }
+
+    /// The page's fragment, still percent-encoded (unlike the one eframe hands us.)
+    pub fn fragment() -> Option<String> {
+        web_sys::window()?.location().hash().ok()
+    }
+
+    pub fn set_fragment(fragment: &str) {
+        if let Some(window) = web_sys::window() {
+            let _ = window.location().set_hash(fragment);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut rules = [false; rulesets::ALL_RULESETS.len()];
+        rules[0] = true;
+        rules[2] = true;
+
+        let shared = Shared {
+            lines: String::from("0|A → □B|PR; 0|A|PR; 0|□B|→E 1, 2"),
+            conclusions: vec![String::from("□B"), String::from("A ∧ □B")],
+            rules,
+            require_all: true,
+        };
+
+        let fragment = encode(&shared);
+
+        assert!( fragment.chars().all(|c| c.is_ascii_alphanumeric() || "%-_.~=&".contains(c) ) );
+        assert_eq!( decode(&fragment), Some( shared.clone() ) );
+        assert_eq!( decode(&format!("#{fragment}")), Some( shared.clone() ) );
+
+        // Older links don't say whether every conclusion is required.
+        let old = fragment.replace("&a=1", "");
+        assert_eq!( decode(&old), Some(Shared { require_all: false, ..shared }) );
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!( decode(""), None );
+        assert_eq!( decode("#p=0%7CA%7CPR&c=A"), None );
+        assert_eq!( decode("p=0%7CA%7CPR&c=A&r=1"), None );
+        assert_eq!( decode("p=%ZZ&c=A&r=1000000"), None );
+        assert_eq!( decode("p=%FF&c=A&r=1000000"), None );
+        assert_eq!( decode("p=A&c=A&r=1000000&x=1"), None );
+        assert_eq!( decode("p=A&c=A&r=1000000&a=2"), None );
+    }
+}